@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::position::Position;
+
+// Which buttons a chord was made with.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ChordButtons {
+    Left,
+    LeftRight,
+    Middle,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub enum Click {
+    Reveal(Position, Duration),
+    Flag(Position, Duration),
+    // A right click that turned a flag into a question mark.
+    Question(Position, Duration),
+    Chord(Position, Duration, ChordButtons),
+}
+
+impl Click {
+    pub fn position(&self) -> Position {
+        match self {
+            Self::Reveal(pos, _)
+            | Self::Flag(pos, _)
+            | Self::Question(pos, _)
+            | Self::Chord(pos, _, _) => *pos,
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        match self {
+            Self::Reveal(_, duration)
+            | Self::Flag(_, duration)
+            | Self::Question(_, duration)
+            | Self::Chord(_, duration, _) => *duration,
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{tile::Tile, visibility::Visibility};
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct Field {
+    tile: Tile,
+    visibility: Visibility,
+}
+
+impl Field {
+    pub fn new() -> Self {
+        Self {
+            tile: Tile::Empty,
+            visibility: Visibility::Closed,
+        }
+    }
+
+    pub fn get_tile(&self) -> Tile {
+        self.tile
+    }
+
+    pub fn get_visibility(&self) -> Visibility {
+        self.visibility
+    }
+
+    /* #region Tile checks */
+    pub fn is_mine(&self) -> bool {
+        matches!(self.tile, Tile::Mine)
+    }
+    /* #endregion */
+
+    /* #region Tile changes */
+    pub fn set_number(&mut self, number: usize) {
+        if number == 0 {
+            self.tile = Tile::Empty;
+        } else {
+            self.tile = Tile::Number(number.into());
+        }
+    }
+
+    pub fn set_mine(&mut self) {
+        self.tile = Tile::Mine;
+    }
+    /* #endregion */
+
+    /* #region Visibility checks */
+    pub fn is_open(&self) -> bool {
+        matches!(self.visibility, Visibility::Open)
+    }
+
+    pub fn is_closed(&self) -> bool {
+        matches!(self.visibility, Visibility::Closed)
+    }
+
+    pub fn is_flagged(&self) -> bool {
+        matches!(self.visibility, Visibility::Flagged)
+    }
+
+    pub fn is_questioned(&self) -> bool {
+        matches!(self.visibility, Visibility::Questioned)
+    }
+    /* #endregion */
+
+    /* #region Visiblity changes */
+    pub fn open(&mut self) {
+        self.visibility = Visibility::Open;
+    }
+
+    pub fn close(&mut self) {
+        self.visibility = Visibility::Closed;
+    }
+
+    pub fn flag(&mut self) {
+        self.visibility = Visibility::Flagged;
+    }
+
+    pub fn question(&mut self) {
+        self.visibility = Visibility::Questioned;
+    }
+    /* #endregion */
+}
+
+impl Default for Field {
+    fn default() -> Self {
+        Self::new()
+    }
+}
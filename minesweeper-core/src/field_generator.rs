@@ -0,0 +1,69 @@
+pub mod no_guess_field_generator;
+pub mod play_field_generator;
+pub mod replay_field_generator;
+
+use rand::Rng;
+
+use crate::{field::Field, position::Position};
+
+pub trait FieldGenerator {
+    fn generate(
+        &self,
+        size: (usize, usize),
+        mine_amount: usize,
+        dont_surround: &Position,
+    ) -> Vec<Field>;
+}
+
+impl<TheFieldGenerator> FieldGenerator for Box<TheFieldGenerator>
+where
+    TheFieldGenerator: FieldGenerator + ?Sized,
+{
+    fn generate(
+        &self,
+        size: (usize, usize),
+        mine_amount: usize,
+        dont_surround: &Position,
+    ) -> Vec<Field> {
+        (**self).generate(size, mine_amount, dont_surround)
+    }
+}
+
+pub(crate) fn place_mines(
+    rng: &mut impl Rng,
+    size: (usize, usize),
+    mine_amount: usize,
+    dont_surround: &Position,
+) -> Vec<Field> {
+    let mut fields = vec![Field::new(); size.0 * size.1];
+
+    let mut mines = 0;
+    while mines < mine_amount {
+        let index = rng.gen_range(0..size.0 * size.1);
+
+        if fields[index].is_mine() || dont_surround.is_near(&Position::from_index(index, size)) {
+            continue;
+        }
+
+        fields[index].set_mine();
+        mines += 1;
+    }
+
+    fields
+}
+
+pub(crate) fn set_numbers(fields: &mut [Field], size: (usize, usize)) {
+    for index in 0..size.0 * size.1 {
+        if fields[index].is_mine() {
+            continue;
+        }
+
+        let mines = Position::from_index(index, size)
+            .neighbors(size)
+            .into_iter()
+            .filter(|pos| fields[pos.index(size)].is_mine())
+            .count();
+
+        fields[index].set_number(mines);
+    }
+}
@@ -0,0 +1,236 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    field::Field,
+    position::Position,
+    solver::{CellState, Solver},
+    tile::Tile,
+};
+
+use super::{place_mines, set_numbers, FieldGenerator};
+
+const MAX_ATTEMPTS: usize = 20;
+const MAX_REPAIRS: usize = 100;
+
+pub struct NoGuessFieldGenerator {
+    pub seed: u64,
+}
+
+impl NoGuessFieldGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    // Whether the board can be solved from the first click without guessing. `generate` gives up
+    // after a while and returns a board that can't be, which happens on very dense boards.
+    pub fn is_solvable(
+        fields: &[Field],
+        size: (usize, usize),
+        mine_amount: usize,
+        first_click: &Position,
+    ) -> bool {
+        let cells = Self::play_out(&Solver::new(size, mine_amount), fields, size, first_click);
+        Self::all_opened(&cells, mine_amount)
+    }
+
+    fn all_opened(cells: &[CellState], mine_amount: usize) -> bool {
+        cells
+            .iter()
+            .filter(|cell| matches!(cell, CellState::Open(_)))
+            .count()
+            == cells.len() - mine_amount
+    }
+
+    fn play_out(
+        solver: &Solver,
+        fields: &[Field],
+        size: (usize, usize),
+        dont_surround: &Position,
+    ) -> Vec<CellState> {
+        let mut cells = vec![CellState::Closed; fields.len()];
+        Self::open(&mut cells, fields, size, dont_surround.index(size));
+
+        loop {
+            let solution = solver.solve_lazily(&cells);
+            if solution.safe.is_empty() {
+                return cells;
+            }
+
+            for mine in solution.mines {
+                cells[mine.index(size)] = CellState::Flagged;
+            }
+            for safe in solution.safe {
+                Self::open(&mut cells, fields, size, safe.index(size));
+            }
+        }
+    }
+
+    fn open(cells: &mut [CellState], fields: &[Field], size: (usize, usize), index: usize) {
+        let mut stack = vec![index];
+        while let Some(index) = stack.pop() {
+            if matches!(cells[index], CellState::Open(_)) || fields[index].is_mine() {
+                continue;
+            }
+
+            match fields[index].get_tile() {
+                Tile::Number(num) => cells[index] = CellState::Open(num.into()),
+                _ => {
+                    cells[index] = CellState::Open(0);
+                    stack.extend(
+                        Position::from_index(index, size)
+                            .neighbors(size)
+                            .iter()
+                            .map(|pos| pos.index(size)),
+                    );
+                }
+            }
+        }
+    }
+
+    fn repair(
+        rng: &mut impl Rng,
+        fields: &mut [Field],
+        cells: &[CellState],
+        size: (usize, usize),
+        dont_surround: &Position,
+    ) -> bool {
+        let touches_open = |index: usize| {
+            Position::from_index(index, size)
+                .neighbors(size)
+                .iter()
+                .any(|pos| matches!(cells[pos.index(size)], CellState::Open(_)))
+        };
+
+        let stuck_mines: Vec<usize> = (0..fields.len())
+            .filter(|&index| {
+                cells[index] == CellState::Closed && fields[index].is_mine() && touches_open(index)
+            })
+            .collect();
+        let free: Vec<usize> = (0..fields.len())
+            .filter(|&index| {
+                cells[index] == CellState::Closed
+                    && !fields[index].is_mine()
+                    && !touches_open(index)
+                    && !dont_surround.is_near(&Position::from_index(index, size))
+            })
+            .collect();
+
+        if stuck_mines.is_empty() || free.is_empty() {
+            return false;
+        }
+
+        fields[stuck_mines[rng.gen_range(0..stuck_mines.len())]].set_number(0);
+        fields[free[rng.gen_range(0..free.len())]].set_mine();
+        set_numbers(fields, size);
+
+        true
+    }
+}
+
+impl FieldGenerator for NoGuessFieldGenerator {
+    fn generate(
+        &self,
+        size: (usize, usize),
+        mine_amount: usize,
+        dont_surround: &Position,
+    ) -> Vec<Field> {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let solver = Solver::new(size, mine_amount);
+
+        let mut fields = Vec::new();
+        for _ in 0..MAX_ATTEMPTS {
+            fields = place_mines(&mut rng, size, mine_amount, dont_surround);
+            set_numbers(&mut fields, size);
+
+            for _ in 0..MAX_REPAIRS {
+                let cells = Self::play_out(&solver, &fields, size, dont_surround);
+                if Self::all_opened(&cells, mine_amount) {
+                    return fields;
+                }
+
+                if !Self::repair(&mut rng, &mut fields, &cells, size, dont_surround) {
+                    break;
+                }
+            }
+        }
+
+        fields
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_board(fields: &[Field], size: (usize, usize), mine_amount: usize, first: &Position) {
+        assert_eq!(
+            fields.iter().filter(|field| field.is_mine()).count(),
+            mine_amount
+        );
+        for pos in first.neighbors(size).iter().chain([first]) {
+            assert!(!fields[pos.index(size)].is_mine());
+        }
+
+        // Repairs move mines around, so the numbers have to be redone with them.
+        let mut renumbered = fields.to_vec();
+        set_numbers(&mut renumbered, size);
+        for (field, renumbered) in fields.iter().zip(&renumbered) {
+            assert!(field.get_tile() == renumbered.get_tile());
+        }
+    }
+
+    #[test]
+    fn boards_are_solvable_from_the_first_click() {
+        let (size, mine_amount) = ((30, 16), 99);
+        let first = Position { x: 15, y: 8 };
+
+        for seed in 0..10 {
+            let fields = NoGuessFieldGenerator::new(seed).generate(size, mine_amount, &first);
+
+            check_board(&fields, size, mine_amount, &first);
+            assert!(NoGuessFieldGenerator::is_solvable(
+                &fields,
+                size,
+                mine_amount,
+                &first
+            ));
+        }
+    }
+
+    #[test]
+    fn dense_boards_are_still_valid() {
+        let (size, mine_amount) = ((10, 10), 70);
+        let first = Position { x: 5, y: 5 };
+
+        for seed in 0..10 {
+            let fields = NoGuessFieldGenerator::new(seed).generate(size, mine_amount, &first);
+
+            check_board(&fields, size, mine_amount, &first);
+            assert!(NoGuessFieldGenerator::is_solvable(
+                &fields,
+                size,
+                mine_amount,
+                &first
+            ));
+        }
+    }
+
+    #[test]
+    fn gives_up_with_a_valid_board() {
+        // The mine is always in one of the last two cells, both next to the same two 1s, and
+        // there's nowhere else to move it, so every attempt runs out.
+        let (size, mine_amount) = ((3, 2), 1);
+        let first = Position { x: 0, y: 0 };
+
+        let fields = NoGuessFieldGenerator::new(7).generate(size, mine_amount, &first);
+
+        check_board(&fields, size, mine_amount, &first);
+        assert!(!NoGuessFieldGenerator::is_solvable(
+            &fields,
+            size,
+            mine_amount,
+            &first
+        ));
+    }
+}
@@ -0,0 +1,32 @@
+use crate::{field::Field, position::Position};
+
+use super::{set_numbers, FieldGenerator};
+
+pub struct ReplayFieldGenerator {
+    pub mines: Vec<Position>,
+}
+
+impl ReplayFieldGenerator {
+    pub fn new(mines: Vec<Position>) -> Self {
+        Self { mines }
+    }
+}
+
+impl FieldGenerator for ReplayFieldGenerator {
+    fn generate(
+        &self,
+        size: (usize, usize),
+        _mine_amount: usize,
+        _dont_surround: &Position,
+    ) -> Vec<Field> {
+        let mut fields = vec![Field::new(); size.0 * size.1];
+
+        for mine in self.mines.iter() {
+            fields[mine.index(size)].set_mine();
+        }
+
+        set_numbers(&mut fields, size);
+
+        fields
+    }
+}
@@ -0,0 +1,217 @@
+use std::ops::{Index, IndexMut};
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    field::Field, field_generator::FieldGenerator, position::Position, tile::Tile,
+    visibility::Visibility, won::Won,
+};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Fields {
+    fields: Vec<Field>,
+    size: (usize, usize),
+    mine_amount: usize,
+
+    pub mines_left: i32,
+    revealed: usize,
+
+    pub won: Won,
+}
+
+impl Fields {
+    pub fn new<TheFieldGenerator>(
+        field_generator: &TheFieldGenerator,
+        size: (usize, usize),
+        mine_amount: usize,
+        dont_surround: &Position,
+    ) -> Self
+    where
+        TheFieldGenerator: FieldGenerator,
+    {
+        Self {
+            fields: field_generator.generate(size, mine_amount, dont_surround),
+            size,
+            mine_amount,
+
+            mines_left: mine_amount as i32,
+            revealed: 0,
+
+            won: Won::None,
+        }
+    }
+
+    pub fn reveal(&mut self, position: &Position, first: bool) -> Option<bool> {
+        match self[position].get_visibility() {
+            Visibility::Closed | Visibility::Questioned => {
+                self[position].open();
+                self.revealed += 1;
+
+                match self[position].get_tile() {
+                    Tile::Empty => {
+                        for neighbor in position.neighbors(self.size) {
+                            self.reveal(&neighbor, false);
+                        }
+                    }
+                    Tile::Mine => {
+                        self.won.lose(*position);
+                        for field in &mut self.fields {
+                            if field.is_mine() && !field.is_flagged() {
+                                field.open();
+                            }
+                        }
+                    }
+                    Tile::Number(_) => {}
+                }
+
+                Some(false)
+            }
+            Visibility::Open => {
+                if first && self.chord(position) {
+                    Some(true)
+                } else {
+                    None
+                }
+            }
+            Visibility::Flagged => None,
+        }
+    }
+
+    // Opens the neighbors of a number that has as many flags around it, returns whether it did.
+    pub fn chord(&mut self, position: &Position) -> bool {
+        if !self[position].is_open() {
+            return false;
+        }
+
+        if let Tile::Number(num) = self[position].get_tile() {
+            let mut flagged = 0;
+            let mut closed = false;
+
+            for neighbor in position.neighbors(self.size) {
+                if self[&neighbor].is_flagged() {
+                    flagged += 1;
+                } else if !self[&neighbor].is_open() {
+                    closed = true;
+                }
+            }
+
+            // Question marks don't count as flags, so chording opens them too.
+            if closed && num == flagged {
+                for neighbor in position.neighbors(self.size) {
+                    if !self[&neighbor].is_open() && !self[&neighbor].is_flagged() {
+                        self.reveal(&neighbor, false);
+                    }
+                }
+
+                return true;
+            }
+        }
+
+        false
+    }
+
+    pub fn flag(&mut self, position: &Position) -> Option<bool> {
+        match self[position].get_visibility() {
+            Visibility::Closed => {
+                self[position].flag();
+                self.mines_left -= 1;
+                Some(true)
+            }
+            Visibility::Flagged => {
+                self[position].close();
+                self.mines_left += 1;
+                Some(false)
+            }
+            Visibility::Questioned => {
+                self[position].close();
+                Some(false)
+            }
+            Visibility::Open => {
+                if let Tile::Number(num) = self[position].get_tile() {
+                    let mut not_open = 0;
+                    let mut any_closed = false;
+
+                    for neighbor in position.neighbors(self.size) {
+                        if !self[&neighbor].is_open() {
+                            not_open += 1;
+                        }
+                        if !self[&neighbor].is_open() && !self[&neighbor].is_flagged() {
+                            any_closed = true;
+                        }
+                    }
+
+                    if any_closed && num == not_open {
+                        for neighbor in position.neighbors(self.size) {
+                            if !self[&neighbor].is_open() && !self[&neighbor].is_flagged() {
+                                self[&neighbor].flag();
+                                self.mines_left -= 1;
+                            }
+                        }
+
+                        return Some(true);
+                    }
+                }
+
+                None
+            }
+        }
+    }
+
+    // Puts a question mark on a closed or flagged field.
+    pub fn question(&mut self, position: &Position) -> bool {
+        match self[position].get_visibility() {
+            Visibility::Closed => {
+                self[position].question();
+                true
+            }
+            Visibility::Flagged => {
+                self[position].question();
+                self.mines_left += 1;
+                true
+            }
+            Visibility::Open | Visibility::Questioned => false,
+        }
+    }
+
+    pub fn check_won(&mut self) {
+        if self.won.still_playing() && self.revealed == self.size.0 * self.size.1 - self.mine_amount
+        {
+            self.won.win();
+        }
+    }
+
+    pub fn get_fields(&self) -> &Vec<Field> {
+        &self.fields
+    }
+
+    pub fn get_size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    pub fn get_mine_amount(&self) -> usize {
+        self.mine_amount
+    }
+
+    pub fn get_mines(&self) -> Vec<Position> {
+        self.fields
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.is_mine())
+            .map(|(i, _)| Position::from_index(i, self.size))
+            .collect()
+    }
+}
+
+impl Index<&Position> for Fields {
+    type Output = Field;
+
+    fn index(&self, index: &Position) -> &Self::Output {
+        &self.fields[index.index(self.size)]
+    }
+}
+
+impl IndexMut<&Position> for Fields {
+    fn index_mut(&mut self, index: &Position) -> &mut Self::Output {
+        &mut self.fields[index.index(self.size)]
+    }
+}
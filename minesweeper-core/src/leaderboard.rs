@@ -0,0 +1,98 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde_derive::{Deserialize, Serialize};
+
+const MAX_ENTRIES_PER_CATEGORY: usize = 20;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Category {
+    pub size: (usize, usize),
+    pub mines: usize,
+    pub no_guess: bool,
+    pub flagging: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Entry {
+    pub category: Category,
+    pub duration: Duration,
+    pub bbbv_per_second: f64,
+    pub date: String,
+    pub replay: Option<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Leaderboard {
+    entries: Vec<Entry>,
+}
+
+impl Leaderboard {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).map_err(|e| e.to_string()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+
+        fs::write(
+            path,
+            serde_json::to_string(self).map_err(|e| e.to_string())?,
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    pub fn add(&mut self, entry: Entry) -> Option<usize> {
+        let rank = self
+            .entries
+            .iter()
+            .filter(|other| other.category == entry.category && other.duration <= entry.duration)
+            .count();
+        if rank >= MAX_ENTRIES_PER_CATEGORY {
+            return None;
+        }
+
+        let category = entry.category;
+        self.entries.push(entry);
+        self.entries.sort_by(|a, b| {
+            a.category
+                .cmp(&b.category)
+                .then(a.duration.cmp(&b.duration))
+        });
+
+        let mut kept = 0;
+        self.entries.retain(|entry| {
+            if entry.category == category {
+                kept += 1;
+                kept <= MAX_ENTRIES_PER_CATEGORY
+            } else {
+                true
+            }
+        });
+
+        Some(rank)
+    }
+
+    pub fn categories(&self) -> Vec<Category> {
+        let mut categories: Vec<Category> =
+            self.entries.iter().map(|entry| entry.category).collect();
+        categories.dedup();
+        categories
+    }
+
+    pub fn entries(&self, category: &Category) -> Vec<&Entry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.category == *category)
+            .collect()
+    }
+}
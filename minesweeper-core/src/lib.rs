@@ -0,0 +1,16 @@
+pub mod click;
+pub mod field;
+pub mod field_generator;
+pub mod fields;
+pub mod leaderboard;
+pub mod mouse_event;
+pub mod number;
+pub mod position;
+pub mod replay_file;
+pub mod saved_game;
+pub mod solver;
+pub mod stats;
+pub mod tile;
+mod utils;
+pub mod visibility;
+pub mod won;
@@ -0,0 +1,146 @@
+use std::time::Duration;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::position::Position;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MouseAction {
+    Move,
+    Down(MouseButton),
+    Up(MouseButton),
+}
+
+// x and y are measured in cells from the top left corner of the board.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct MouseEvent {
+    pub action: MouseAction,
+    pub x: f32,
+    pub y: f32,
+    pub duration: Duration,
+}
+
+impl MouseEvent {
+    pub fn new(action: MouseAction, x: f32, y: f32, duration: Duration) -> Self {
+        Self {
+            action,
+            x,
+            y,
+            duration,
+        }
+    }
+
+    pub fn position(&self, size: (usize, usize)) -> Option<Position> {
+        if self.x < 0.0 || self.y < 0.0 {
+            None
+        } else {
+            Position::new(self.x as usize, self.y as usize, size)
+        }
+    }
+}
+
+// Follows the buttons through their presses and releases, in the order they happened, to tell
+// which click every release makes. Letting go of either button while the other one is held
+// chords, and letting go of the other one after that doesn't click again.
+#[derive(Default)]
+pub struct MouseButtons {
+    left: bool,
+    right: bool,
+    chorded: bool,
+}
+
+impl MouseButtons {
+    pub fn chorded(&self) -> bool {
+        self.chorded
+    }
+
+    // The button that clicked and whether both were held, if the action finished a click.
+    pub fn handle(&mut self, action: MouseAction) -> Option<(MouseButton, bool)> {
+        let click = match action {
+            MouseAction::Move => None,
+            MouseAction::Down(button) => {
+                match button {
+                    MouseButton::Left => self.left = true,
+                    MouseButton::Right => self.right = true,
+                    MouseButton::Middle => {}
+                }
+                None
+            }
+            MouseAction::Up(MouseButton::Middle) => Some((MouseButton::Middle, false)),
+            MouseAction::Up(button) => {
+                let other = if button == MouseButton::Left {
+                    self.left = false;
+                    self.right
+                } else {
+                    self.right = false;
+                    self.left
+                };
+
+                let click = if other {
+                    Some((button, true))
+                } else if !self.chorded {
+                    Some((button, false))
+                } else {
+                    None
+                };
+                self.chorded |= other;
+                click
+            }
+        };
+
+        if !self.left && !self.right {
+            self.chorded = false;
+        }
+        click
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clicks(actions: &[MouseAction]) -> Vec<(MouseButton, bool)> {
+        let mut buttons = MouseButtons::default();
+        actions
+            .iter()
+            .filter_map(|action| buttons.handle(*action))
+            .collect()
+    }
+
+    #[test]
+    fn single_clicks() {
+        assert!(
+            clicks(&[
+                MouseAction::Down(MouseButton::Left),
+                MouseAction::Up(MouseButton::Left),
+                MouseAction::Down(MouseButton::Right),
+                MouseAction::Up(MouseButton::Right),
+            ]) == [(MouseButton::Left, false), (MouseButton::Right, false)]
+        );
+    }
+
+    #[test]
+    fn releasing_both_buttons_at_once_chords_once() {
+        // Both releases arrive in the same frame, one right after the other.
+        for (first, second) in [
+            (MouseButton::Left, MouseButton::Right),
+            (MouseButton::Right, MouseButton::Left),
+        ] {
+            assert!(
+                clicks(&[
+                    MouseAction::Down(MouseButton::Left),
+                    MouseAction::Down(MouseButton::Right),
+                    MouseAction::Up(first),
+                    MouseAction::Up(second),
+                ]) == [(first, true)]
+            );
+        }
+    }
+}
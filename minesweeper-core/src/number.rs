@@ -1,5 +1,7 @@
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub(crate) enum Number {
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Number {
     One,
     Two,
     Three,
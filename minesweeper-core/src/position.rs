@@ -0,0 +1,41 @@
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Position {
+    pub fn new(x: usize, y: usize, size: (usize, usize)) -> Option<Self> {
+        if x < size.0 && y < size.1 {
+            Some(Self { x, y })
+        } else {
+            None
+        }
+    }
+
+    pub fn from_index(index: usize, size: (usize, usize)) -> Self {
+        Self {
+            x: index % size.0,
+            y: index / size.0,
+        }
+    }
+
+    pub fn index(&self, size: (usize, usize)) -> usize {
+        self.y * size.0 + self.x
+    }
+
+    pub fn is_near(&self, other: &Self) -> bool {
+        self.x.abs_diff(other.x) <= 1 && self.y.abs_diff(other.y) <= 1
+    }
+
+    pub fn neighbors(&self, size: (usize, usize)) -> Vec<Self> {
+        (self.x.saturating_sub(1)..=self.x + 1)
+            .flat_map(|x| {
+                (self.y.saturating_sub(1)..=self.y + 1).filter_map(move |y| Self::new(x, y, size))
+            })
+            .filter(|pos| pos != self)
+            .collect()
+    }
+}
@@ -0,0 +1,550 @@
+mod binary;
+mod export;
+mod import;
+pub mod metadata;
+mod migration;
+pub mod signature;
+
+use std::{fs, path::Path, time::Duration};
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    click::Click,
+    field_generator::replay_field_generator::ReplayFieldGenerator,
+    fields::Fields,
+    mouse_event::{MouseAction, MouseButton, MouseEvent},
+    position::Position,
+    stats::{ClickKind, Stats},
+    tile::Tile,
+    utils,
+    won::Won,
+};
+
+use self::{
+    metadata::{GameResult, Metadata},
+    signature::{ReplaySignature, SignatureStatus, PUBLIC_KEY_LENGTH},
+};
+
+pub const REPLAY_FILE_VERSION: u32 = 9;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ReplayFormat {
+    Json,
+    Binary,
+    CompressedBinary,
+    Avf,
+    Rmv,
+    Raw,
+}
+
+impl ReplayFormat {
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "json" => Some(Self::Json),
+            "msr" => Some(Self::Binary),
+            "msrz" => Some(Self::CompressedBinary),
+            "avf" => Some(Self::Avf),
+            "rmv" => Some(Self::Rmv),
+            "rawvf" => Some(Self::Raw),
+            _ => None,
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Self {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(Self::from_extension)
+            .unwrap_or(Self::Json)
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Binary => "msr",
+            Self::CompressedBinary => "msrz",
+            Self::Avf => "avf",
+            Self::Rmv => "rmv",
+            Self::Raw => "rawvf",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReplayFile {
+    pub version: u32,
+    pub mines: Vec<Position>,
+    pub size: (usize, usize),
+    pub clicks: Vec<Click>,
+    pub duration: Duration,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub stats: Option<Stats>,
+    #[serde(default)]
+    pub mouse_events: Vec<MouseEvent>,
+    #[serde(default)]
+    pub imported_from: Option<String>,
+    #[serde(default)]
+    pub signature: Option<ReplaySignature>,
+    #[serde(default)]
+    pub metadata: Option<Metadata>,
+}
+
+impl ReplayFile {
+    pub fn new(
+        mines: Vec<Position>,
+        size: (usize, usize),
+        clicks: Vec<Click>,
+        duration: Duration,
+        seed: Option<u64>,
+        mouse_events: Vec<MouseEvent>,
+    ) -> Self {
+        let mut replay_file = Self {
+            version: REPLAY_FILE_VERSION,
+            mines,
+            size,
+            clicks,
+            duration,
+            seed,
+            stats: None,
+            mouse_events,
+            imported_from: None,
+            signature: None,
+            metadata: None,
+        };
+        replay_file.stats = Some(Stats::from_replay(&replay_file));
+
+        replay_file
+    }
+
+    pub fn sign(&mut self, secret_key: &[u8; 32]) {
+        self.signature = Some(signature::sign(self, secret_key));
+    }
+
+    pub fn signature_status(&self, trusted_keys: &[[u8; PUBLIC_KEY_LENGTH]]) -> SignatureStatus {
+        signature::verify(self, trusted_keys)
+    }
+
+    // The oldest version that can hold this replay, so that replays without question marks or
+    // chords still open in builds from before those were added. The middle button came with
+    // chords, so pressing it at all needs their version. JSON readers skip fields they
+    // don't know, but binary replays need version 9 to hold the declared mine count.
+    pub(crate) fn oldest_version(&self, binary: bool) -> u32 {
+        if binary
+            && self
+                .metadata
+                .as_ref()
+                .is_some_and(|metadata| metadata.mine_amount.is_some())
+        {
+            9
+        } else if self
+            .clicks
+            .iter()
+            .any(|click| matches!(click, Click::Chord(..)))
+            || self.mouse_events.iter().any(|event| {
+                matches!(
+                    event.action,
+                    MouseAction::Down(MouseButton::Middle) | MouseAction::Up(MouseButton::Middle)
+                )
+            })
+        {
+            8
+        } else if self
+            .clicks
+            .iter()
+            .any(|click| matches!(click, Click::Question(..)))
+        {
+            7
+        } else {
+            6
+        }
+    }
+
+    // Plays a click on the board the way the game does, creating the board on the first reveal.
+    // Returns what the click counts as in the stats and whether it did anything.
+    pub fn apply_click(&self, fields: &mut Option<Fields>, click: &Click) -> (ClickKind, bool) {
+        if let Click::Reveal(pos, _) = click {
+            fields.get_or_insert_with(|| {
+                Fields::new(
+                    &ReplayFieldGenerator::new(self.mines.clone()),
+                    self.size,
+                    self.mines.len(),
+                    pos,
+                )
+            });
+        }
+
+        let Some(fields) = fields.as_mut().filter(|fields| fields.won.still_playing()) else {
+            return match click {
+                Click::Reveal(..) => (ClickKind::Left, false),
+                Click::Flag(..) => (ClickKind::Right, false),
+                Click::Question(..) => (ClickKind::Question, false),
+                Click::Chord(..) => (ClickKind::Chord, false),
+            };
+        };
+
+        // Older replays chord by clicking an open number instead of recording a chord.
+        let pos = &click.position();
+        let on_number = fields[pos].is_open() && matches!(fields[pos].get_tile(), Tile::Number(_));
+
+        match click {
+            Click::Reveal(pos, _) => {
+                let result = fields.reveal(pos, true);
+                fields.check_won();
+                if on_number {
+                    (ClickKind::Chord, result == Some(true))
+                } else {
+                    (ClickKind::Left, result == Some(false))
+                }
+            }
+            Click::Flag(pos, _) => {
+                let result = fields.flag(pos);
+                if on_number {
+                    (ClickKind::Chord, result.is_some())
+                } else {
+                    (
+                        ClickKind::Right,
+                        result == Some(true) && fields[pos].is_mine(),
+                    )
+                }
+            }
+            Click::Question(pos, _) => (ClickKind::Question, fields.question(pos)),
+            Click::Chord(pos, _, _) => {
+                let opened = fields.chord(pos);
+                fields.check_won();
+                (ClickKind::Chord, opened)
+            }
+        }
+    }
+
+    // Whether any flag was placed, question marks don't count.
+    pub fn flagged(&self) -> bool {
+        self.clicks
+            .iter()
+            .any(|click| matches!(click, Click::Flag(..)))
+    }
+
+    // The board after the first `click` clicks, or None if nothing has been revealed yet.
+    pub fn fields_at(&self, click: usize) -> Option<Fields> {
+        let mut fields = None;
+        for click in self.clicks.iter().take(click) {
+            self.apply_click(&mut fields, click);
+        }
+
+        fields
+    }
+
+    pub fn validate(&self) -> Option<String> {
+        if self.clicks.is_empty() {
+            return Some("No clicks".to_string());
+        }
+
+        if self
+            .clicks
+            .iter()
+            .any(|click| click.position().x >= self.size.0 || click.position().y >= self.size.1)
+        {
+            return Some("Click out of bounds".to_string());
+        }
+
+        if self
+            .mines
+            .iter()
+            .any(|mine| mine.x >= self.size.0 || mine.y >= self.size.1)
+        {
+            return Some("Mine out of bounds".to_string());
+        }
+
+        if !utils::has_unique_elements(&self.mines) {
+            return Some("Duplicate mines".to_string());
+        }
+
+        // The first click needs its 3x3 area free, like the settings menu enforces.
+        if self.imported_from.is_none()
+            && (self.size.0 < 5
+                || self.size.1 < 5
+                || self.mines.len() > self.size.0 * self.size.1 - 9)
+        {
+            return Some(format!(
+                "{} mines don't fit on a {}x{} board",
+                self.mines.len(),
+                self.size.0,
+                self.size.1
+            ));
+        }
+
+        if let Some(mine_amount) = self
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.mine_amount)
+            .filter(|&mine_amount| mine_amount != self.mines.len())
+        {
+            return Some(format!(
+                "{} mines, but the game was set up with {}",
+                self.mines.len(),
+                mine_amount
+            ));
+        }
+
+        // Flags placed before the first reveal don't create the board, so the mines are kept away
+        // from the first reveal rather than the first click.
+        let first_reveal = self
+            .clicks
+            .iter()
+            .find(|click| matches!(click, Click::Reveal(..)));
+        if let Some(first_reveal) = first_reveal.filter(|_| self.imported_from.is_none()) {
+            if self
+                .mines
+                .iter()
+                .any(|mine| first_reveal.position().is_near(mine))
+            {
+                return Some("Invalid board generation: mine near first click".to_string());
+            }
+        }
+
+        if let Some(index) = (1..self.clicks.len())
+            .find(|&index| self.clicks[index].duration() < self.clicks[index - 1].duration())
+        {
+            return Some(format!("Click {}: earlier than the click before it", index));
+        }
+
+        if self.clicks.last().unwrap().duration() > self.duration
+            || self.duration - self.clicks.last().unwrap().duration() > Duration::from_secs(1)
+        {
+            return Some("Invalid duration".to_string());
+        }
+
+        if self
+            .mouse_events
+            .windows(2)
+            .any(|events| events[0].duration > events[1].duration)
+        {
+            return Some("Mouse events out of order".to_string());
+        }
+
+        self.simulate()
+    }
+
+    fn simulate(&self) -> Option<String> {
+        let mut fields = None;
+        for (index, click) in self.clicks.iter().enumerate() {
+            if fields
+                .as_ref()
+                .is_some_and(|fields: &Fields| !fields.won.still_playing())
+            {
+                return Some(format!("Click {}: the game had already ended", index));
+            }
+
+            self.apply_click(&mut fields, click);
+        }
+
+        let last = self.clicks.len() - 1;
+        let won = match fields.map(|fields| fields.won) {
+            Some(Won::Won) => true,
+            Some(Won::Lost(_)) => false,
+            _ => return Some(format!("Click {}: the game never ends", last)),
+        };
+
+        if let Some(stats) = self.stats.filter(|stats| stats.won != won) {
+            return Some(format!(
+                "Click {}: the game is {}, but the replay says it was {}",
+                last,
+                if won { "won" } else { "lost" },
+                if stats.won { "won" } else { "lost" }
+            ));
+        }
+
+        if let Some(metadata) = self
+            .metadata
+            .as_ref()
+            .filter(|metadata| (metadata.result == GameResult::Won) != won)
+        {
+            return Some(format!(
+                "Click {}: the game is {}, but the metadata says it was {}",
+                last,
+                if won { "won" } else { "lost" },
+                match metadata.result {
+                    GameResult::Won => "won",
+                    GameResult::Lost => "lost",
+                }
+            ));
+        }
+
+        None
+    }
+
+    pub fn from_string(string: String) -> Result<Self, String> {
+        let value = serde_json::from_str(&string).map_err(|e| e.to_string())?;
+        let file: Self =
+            serde_json::from_value(migration::migrate(value)?).map_err(|e| e.to_string())?;
+
+        if let Some(error) = file.validate() {
+            return Err(error);
+        }
+
+        Ok(file)
+    }
+
+    pub fn from_bytes(bytes: &[u8], format: ReplayFormat) -> Result<Self, String> {
+        let mut file = match format {
+            ReplayFormat::Json => {
+                return Self::from_string(
+                    String::from_utf8(bytes.to_vec()).map_err(|e| e.to_string())?,
+                )
+            }
+            ReplayFormat::Binary | ReplayFormat::CompressedBinary => binary::decode(bytes)?,
+            ReplayFormat::Avf => import::avf(bytes)?,
+            ReplayFormat::Rmv => import::rmv(bytes)?,
+            ReplayFormat::Raw => return Err(format!("Can't load .{} files", format.extension())),
+        };
+
+        if let Some(error) = file.validate() {
+            return Err(error);
+        }
+        file.stats = Some(Stats::from_replay(&file));
+
+        Ok(file)
+    }
+
+    pub fn to_bytes(&self, format: ReplayFormat) -> Result<Vec<u8>, String> {
+        match format {
+            ReplayFormat::Json => serde_json::to_vec(&Self {
+                version: self.oldest_version(false),
+                ..self.clone()
+            })
+            .map_err(|e| e.to_string()),
+            ReplayFormat::Binary => binary::encode(self, false),
+            ReplayFormat::CompressedBinary => binary::encode(self, true),
+            ReplayFormat::Avf => export::avf(self),
+            ReplayFormat::Raw => export::raw(self)
+                .map(String::into_bytes)
+                .map_err(|e| e.to_string()),
+            ReplayFormat::Rmv => Err(format!(
+                "Can't save replays as .{} files",
+                format.extension()
+            )),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|e| e.to_string())?;
+        Self::from_bytes(&bytes, ReplayFormat::from_path(path))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_bytes(ReplayFormat::from_path(path))?).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{
+        field_generator::play_field_generator::PlayFieldGenerator, replay_file::metadata::Generator,
+    };
+
+    // A won game on an 8x8 board: the first reveal, a flag and then every other safe cell revealed
+    // one by one, with the mouse pressed and released on each of them.
+    pub(crate) fn won_game() -> ReplayFile {
+        let size = (8, 8);
+        let first = Position { x: 3, y: 3 };
+        let mut fields = Fields::new(&PlayFieldGenerator::new(7), size, 10, &first);
+        let mines = fields.get_mines();
+
+        fields.reveal(&first, true);
+        fields.flag(&mines[0]);
+        let mut clicks = vec![
+            Click::Reveal(first, Duration::ZERO),
+            Click::Flag(mines[0], Duration::from_millis(100)),
+        ];
+        for index in 0..size.0 * size.1 {
+            let pos = Position::from_index(index, size);
+            if fields.won.still_playing() && fields[&pos].is_closed() && !fields[&pos].is_mine() {
+                fields.reveal(&pos, true);
+                fields.check_won();
+                clicks.push(Click::Reveal(
+                    pos,
+                    Duration::from_millis(100 * clicks.len() as u64),
+                ));
+            }
+        }
+
+        let mut mouse_events = Vec::new();
+        for click in &clicks {
+            let button = match click {
+                Click::Flag(..) => MouseButton::Right,
+                _ => MouseButton::Left,
+            };
+            let (x, y) = (
+                click.position().x as f32 + 0.5,
+                click.position().y as f32 + 0.5,
+            );
+            mouse_events.push(MouseEvent::new(
+                MouseAction::Down(button),
+                x,
+                y,
+                click.duration().saturating_sub(Duration::from_millis(50)),
+            ));
+            mouse_events.push(MouseEvent::new(
+                MouseAction::Up(button),
+                x,
+                y,
+                click.duration(),
+            ));
+        }
+
+        let duration = clicks.last().unwrap().duration() + Duration::from_millis(10);
+        let mut replay_file = ReplayFile::new(mines, size, clicks, duration, Some(7), mouse_events);
+        replay_file.metadata = Some(Metadata::new(
+            "Tester".to_string(),
+            1_700_000_000,
+            "0.1.0".to_string(),
+            Generator::Seeded,
+            false,
+            10,
+            &replay_file,
+        ));
+
+        replay_file
+    }
+
+    #[test]
+    fn question_marks_are_not_flagging() {
+        let mut replay_file = won_game();
+        assert!(replay_file.flagged());
+
+        let Click::Flag(pos, time) = replay_file.clicks[1] else {
+            panic!("The second click should be a flag");
+        };
+        replay_file.clicks[1] = Click::Question(pos, time);
+        assert!(!replay_file.flagged());
+    }
+
+    #[test]
+    fn middle_button_needs_the_chord_version() {
+        let mut replay_file = won_game();
+        assert_eq!(replay_file.oldest_version(false), 6);
+
+        // A middle click on a closed cell does nothing, but the press is still recorded.
+        let event = replay_file.mouse_events[0];
+        replay_file.mouse_events.splice(
+            0..0,
+            [
+                MouseEvent::new(
+                    MouseAction::Down(MouseButton::Middle),
+                    event.x,
+                    event.y,
+                    event.duration,
+                ),
+                MouseEvent::new(
+                    MouseAction::Up(MouseButton::Middle),
+                    event.x,
+                    event.y,
+                    event.duration,
+                ),
+            ],
+        );
+        assert_eq!(replay_file.oldest_version(false), 8);
+    }
+}
@@ -0,0 +1,493 @@
+use std::{
+    io::{Read, Write},
+    time::Duration,
+};
+
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+
+use super::{
+    metadata::{Flagging, GameResult, Generator, Metadata},
+    migration,
+    signature::{ReplaySignature, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH},
+    ReplayFile, REPLAY_FILE_VERSION,
+};
+use crate::{
+    click::{ChordButtons, Click},
+    mouse_event::{MouseAction, MouseButton, MouseEvent},
+    position::Position,
+};
+
+const MAGIC: &[u8; 4] = b"MSWR";
+
+const UNCOMPRESSED: u8 = 0;
+const COMPRESSED: u8 = 1;
+
+const REVEAL: usize = 0;
+const FLAG: usize = 1;
+const QUESTION: usize = 2;
+const LEFT_CHORD: usize = 3;
+const LEFT_RIGHT_CHORD: usize = 4;
+const MIDDLE_CHORD: usize = 5;
+
+// Mouse coordinates are stored in 1/256ths of a cell.
+const MOUSE_PRECISION: f32 = 256.0;
+
+pub(crate) fn encode(replay_file: &ReplayFile, compress: bool) -> Result<Vec<u8>, String> {
+    let version = replay_file.oldest_version(true);
+    let kind_bits = click_kind_bits(version as u64);
+
+    let mut body = Vec::new();
+    let size = replay_file.size;
+
+    write_varint(&mut body, size.0 as u64);
+    write_varint(&mut body, size.1 as u64);
+
+    let mut bitmap = vec![0u8; (size.0 * size.1).div_ceil(8)];
+    for mine in &replay_file.mines {
+        let index = mine.index(size);
+        bitmap[index / 8] |= 1 << (index % 8);
+    }
+    body.extend(bitmap);
+
+    write_varint(&mut body, replay_file.duration.as_nanos() as u64);
+
+    match replay_file.seed {
+        Some(seed) => {
+            body.push(1);
+            write_varint(&mut body, seed);
+        }
+        None => body.push(0),
+    }
+
+    write_varint(&mut body, replay_file.clicks.len() as u64);
+    let mut last = 0;
+    for click in &replay_file.clicks {
+        let kind = match click {
+            Click::Reveal(..) => REVEAL,
+            Click::Flag(..) => FLAG,
+            Click::Question(..) => QUESTION,
+            Click::Chord(_, _, ChordButtons::Left) => LEFT_CHORD,
+            Click::Chord(_, _, ChordButtons::LeftRight) => LEFT_RIGHT_CHORD,
+            Click::Chord(_, _, ChordButtons::Middle) => MIDDLE_CHORD,
+        };
+        write_varint(
+            &mut body,
+            (click.position().index(size) << kind_bits | kind) as u64,
+        );
+
+        let time = click.duration().as_nanos() as i64;
+        write_varint(&mut body, zigzag(time - last));
+        last = time;
+    }
+
+    write_varint(&mut body, replay_file.mouse_events.len() as u64);
+    let (mut last_x, mut last_y, mut last_time) = (0, 0, 0);
+    for event in &replay_file.mouse_events {
+        body.push(match event.action {
+            MouseAction::Move => 0,
+            MouseAction::Down(MouseButton::Left) => 1,
+            MouseAction::Down(MouseButton::Right) => 2,
+            MouseAction::Up(MouseButton::Left) => 3,
+            MouseAction::Up(MouseButton::Right) => 4,
+            MouseAction::Down(MouseButton::Middle) => 5,
+            MouseAction::Up(MouseButton::Middle) => 6,
+        });
+
+        let x = (event.x * MOUSE_PRECISION).round() as i64;
+        let y = (event.y * MOUSE_PRECISION).round() as i64;
+        let time = event.duration.as_nanos() as i64;
+        write_varint(&mut body, zigzag(x.wrapping_sub(last_x)));
+        write_varint(&mut body, zigzag(y.wrapping_sub(last_y)));
+        write_varint(&mut body, zigzag(time - last_time));
+        (last_x, last_y, last_time) = (x, y, time);
+    }
+
+    match &replay_file.imported_from {
+        Some(imported_from) => {
+            body.push(1);
+            write_string(&mut body, imported_from);
+        }
+        None => body.push(0),
+    }
+
+    match replay_file
+        .signature
+        .as_ref()
+        .and_then(ReplaySignature::to_bytes)
+    {
+        Some((public_key, signature)) => {
+            body.push(1);
+            body.extend(public_key);
+            body.extend(signature);
+        }
+        None => body.push(0),
+    }
+
+    match &replay_file.metadata {
+        Some(metadata) => {
+            body.push(1);
+            write_string(&mut body, &metadata.player);
+            write_varint(&mut body, metadata.started_at);
+            write_string(&mut body, &metadata.game_version);
+            body.push(match metadata.generator {
+                Generator::Random => 0,
+                Generator::Seeded => 1,
+                Generator::NoGuess => 2,
+            });
+            body.push(match metadata.flagging {
+                Flagging::Flags => 0,
+                Flagging::NoFlags => 1,
+            });
+            body.push(match metadata.result {
+                GameResult::Won => 0,
+                GameResult::Lost => 1,
+            });
+            body.push(metadata.assisted as u8);
+            if let Some(mine_amount) = metadata.mine_amount {
+                write_varint(&mut body, mine_amount as u64);
+            }
+        }
+        None => body.push(0),
+    }
+
+    let mut bytes = MAGIC.to_vec();
+    write_varint(&mut bytes, version as u64);
+    if compress {
+        bytes.push(COMPRESSED);
+        let mut encoder = DeflateEncoder::new(bytes, Compression::best());
+        encoder.write_all(&body).map_err(|e| e.to_string())?;
+        encoder.finish().map_err(|e| e.to_string())
+    } else {
+        bytes.push(UNCOMPRESSED);
+        bytes.extend(body);
+        Ok(bytes)
+    }
+}
+
+pub(crate) fn decode(bytes: &[u8]) -> Result<ReplayFile, String> {
+    if !bytes.starts_with(MAGIC) {
+        return Err("Not a binary replay file".to_string());
+    }
+
+    let mut reader = Reader {
+        bytes: &bytes[MAGIC.len()..],
+    };
+
+    let version = reader.varint()?;
+    if version > REPLAY_FILE_VERSION as u64 {
+        return Err(migration::newer_version_error(version));
+    }
+
+    let body = match reader.byte()? {
+        UNCOMPRESSED => reader.bytes.to_vec(),
+        COMPRESSED => {
+            let mut body = Vec::new();
+            DeflateDecoder::new(reader.bytes)
+                .read_to_end(&mut body)
+                .map_err(|e| format!("Couldn't decompress replay file: {}", e))?;
+            body
+        }
+        compression => return Err(format!("Unknown compression: {}", compression)),
+    };
+
+    let mut reader = Reader { bytes: &body };
+
+    let size = (reader.varint()? as usize, reader.varint()? as usize);
+    let cells = size
+        .0
+        .checked_mul(size.1)
+        .filter(|cells| *cells > 0)
+        .ok_or_else(|| "Invalid size".to_string())?;
+
+    let bitmap = reader.take(cells.div_ceil(8))?;
+    let mines = (0..cells)
+        .filter(|index| bitmap[index / 8] & (1 << (index % 8)) != 0)
+        .map(|index| Position::from_index(index, size))
+        .collect();
+
+    let duration = Duration::from_nanos(reader.varint()?);
+
+    let seed = match reader.byte()? {
+        0 => None,
+        _ => Some(reader.varint()?),
+    };
+
+    let click_amount = reader.varint()? as usize;
+    let mut clicks = Vec::with_capacity(click_amount.min(reader.bytes.len()));
+    let kind_bits = click_kind_bits(version);
+    let mut last: i64 = 0;
+    for _ in 0..click_amount {
+        let packed = reader.varint()? as usize;
+        let position = Position::from_index(packed >> kind_bits, size);
+
+        last = last
+            .checked_add(unzigzag(reader.varint()?))
+            .ok_or_else(|| "Invalid click time".to_string())?;
+        let time = Duration::from_nanos(
+            u64::try_from(last).map_err(|_| "Negative click time".to_string())?,
+        );
+
+        clicks.push(match packed & ((1 << kind_bits) - 1) {
+            REVEAL => Click::Reveal(position, time),
+            FLAG => Click::Flag(position, time),
+            QUESTION => Click::Question(position, time),
+            LEFT_CHORD => Click::Chord(position, time, ChordButtons::Left),
+            LEFT_RIGHT_CHORD => Click::Chord(position, time, ChordButtons::LeftRight),
+            MIDDLE_CHORD => Click::Chord(position, time, ChordButtons::Middle),
+            kind => return Err(format!("Invalid click kind: {}", kind)),
+        });
+    }
+
+    let mut mouse_events = Vec::new();
+    if version >= 2 {
+        let event_amount = reader.varint()? as usize;
+        mouse_events.reserve(event_amount.min(reader.bytes.len()));
+        let (mut x, mut y, mut time): (i64, i64, i64) = (0, 0, 0);
+        for _ in 0..event_amount {
+            let action = match reader.byte()? {
+                0 => MouseAction::Move,
+                1 => MouseAction::Down(MouseButton::Left),
+                2 => MouseAction::Down(MouseButton::Right),
+                3 => MouseAction::Up(MouseButton::Left),
+                4 => MouseAction::Up(MouseButton::Right),
+                5 => MouseAction::Down(MouseButton::Middle),
+                6 => MouseAction::Up(MouseButton::Middle),
+                action => return Err(format!("Unknown mouse action: {}", action)),
+            };
+
+            x = x.wrapping_add(unzigzag(reader.varint()?));
+            y = y.wrapping_add(unzigzag(reader.varint()?));
+            time = time
+                .checked_add(unzigzag(reader.varint()?))
+                .ok_or_else(|| "Invalid mouse event time".to_string())?;
+
+            mouse_events.push(MouseEvent::new(
+                action,
+                x as f32 / MOUSE_PRECISION,
+                y as f32 / MOUSE_PRECISION,
+                Duration::from_nanos(
+                    u64::try_from(time).map_err(|_| "Negative mouse event time".to_string())?,
+                ),
+            ));
+        }
+    }
+
+    let mut imported_from = None;
+    if version >= 3 && reader.byte()? != 0 {
+        imported_from = Some(reader.string()?);
+    }
+
+    let mut signature = None;
+    if version >= 4 && reader.byte()? != 0 {
+        let public_key = reader.take(PUBLIC_KEY_LENGTH)?.try_into().unwrap();
+        signature = Some(ReplaySignature::from_bytes(
+            public_key,
+            reader.take(SIGNATURE_LENGTH)?.try_into().unwrap(),
+        ));
+    }
+
+    let mut metadata = None;
+    if version >= 5 && reader.byte()? != 0 {
+        metadata = Some(Metadata {
+            player: reader.string()?,
+            started_at: reader.varint()?,
+            game_version: reader.string()?,
+            generator: match reader.byte()? {
+                0 => Generator::Random,
+                1 => Generator::Seeded,
+                2 => Generator::NoGuess,
+                generator => return Err(format!("Unknown generator: {}", generator)),
+            },
+            flagging: match reader.byte()? {
+                0 => Flagging::Flags,
+                1 => Flagging::NoFlags,
+                flagging => return Err(format!("Unknown flagging style: {}", flagging)),
+            },
+            result: match reader.byte()? {
+                0 => GameResult::Won,
+                1 => GameResult::Lost,
+                result => return Err(format!("Unknown result: {}", result)),
+            },
+            assisted: version >= 6 && reader.byte()? != 0,
+            mine_amount: if version >= 9 {
+                Some(reader.varint()? as usize)
+            } else {
+                None
+            },
+        });
+    }
+
+    if !reader.bytes.is_empty() {
+        return Err("Trailing data after replay".to_string());
+    }
+
+    Ok(ReplayFile {
+        version: REPLAY_FILE_VERSION,
+        mines,
+        size,
+        clicks,
+        duration,
+        seed,
+        stats: None,
+        mouse_events,
+        imported_from,
+        signature,
+        metadata,
+    })
+}
+
+// The kind of a click is packed into the low bits of its position, one bit before version 7 and
+// two before version 8.
+fn click_kind_bits(version: u64) -> usize {
+    match version {
+        0..=6 => 1,
+        7 => 2,
+        _ => 3,
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, amount: usize) -> Result<&'a [u8], String> {
+        if self.bytes.len() < amount {
+            return Err("Unexpected end of replay file".to_string());
+        }
+
+        let (taken, rest) = self.bytes.split_at(amount);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err("Invalid varint".to_string())
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let length = self.varint()? as usize;
+        String::from_utf8(self.take(length)?.to_vec()).map_err(|e| e.to_string())
+    }
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn write_string(bytes: &mut Vec<u8>, string: &str) {
+    write_varint(bytes, string.len() as u64);
+    bytes.extend(string.as_bytes());
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay_file::{
+        signature::{public_key, SignatureStatus},
+        tests::won_game,
+        ReplayFormat,
+    };
+
+    fn json(replay_file: &ReplayFile) -> serde_json::Value {
+        let mut value = serde_json::to_value(replay_file).unwrap();
+        value["stats"] = serde_json::Value::Null;
+        value
+    }
+
+    #[test]
+    fn json_to_binary_and_back() {
+        let mut replay_file = won_game();
+        replay_file.sign(&[7; 32]);
+        let replay_file = ReplayFile::from_bytes(
+            &replay_file.to_bytes(ReplayFormat::Json).unwrap(),
+            ReplayFormat::Json,
+        )
+        .unwrap();
+
+        let bytes = replay_file.to_bytes(ReplayFormat::Binary).unwrap();
+        assert!(bytes.starts_with(MAGIC));
+        assert_eq!(bytes[MAGIC.len()], 9);
+        assert_eq!(bytes[MAGIC.len() + 1], UNCOMPRESSED);
+
+        let decoded = ReplayFile::from_bytes(&bytes, ReplayFormat::Binary).unwrap();
+        let json_again = ReplayFile::from_bytes(
+            &decoded.to_bytes(ReplayFormat::Json).unwrap(),
+            ReplayFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(json(&json_again), json(&replay_file));
+        assert!(json_again.signature_status(&[public_key(&[7; 32])]) == SignatureStatus::Signed);
+    }
+
+    #[test]
+    fn compressed() {
+        let replay_file = won_game();
+
+        let bytes = replay_file
+            .to_bytes(ReplayFormat::CompressedBinary)
+            .unwrap();
+        assert_eq!(bytes[MAGIC.len() + 1], COMPRESSED);
+
+        let decoded = ReplayFile::from_bytes(&bytes, ReplayFormat::CompressedBinary).unwrap();
+        assert_eq!(json(&decoded), json(&replay_file));
+    }
+
+    #[test]
+    fn click_kind_widths() {
+        let mut replay_file = won_game();
+        replay_file.metadata = None;
+        let flag = replay_file.clicks[1];
+        let first = replay_file.clicks[0];
+
+        let extra_clicks = [
+            vec![],
+            vec![Click::Question(flag.position(), flag.duration())],
+            vec![
+                Click::Question(flag.position(), flag.duration()),
+                Click::Chord(first.position(), flag.duration(), ChordButtons::Left),
+                Click::Chord(first.position(), flag.duration(), ChordButtons::LeftRight),
+                Click::Chord(first.position(), flag.duration(), ChordButtons::Middle),
+            ],
+        ];
+        // One bit up to version 6, two for question marks in 7 and three for chords in 8.
+        for (extra_clicks, version) in extra_clicks.into_iter().zip([6, 7, 8]) {
+            let mut replay_file = replay_file.clone();
+            replay_file.clicks.splice(2..2, extra_clicks);
+
+            let bytes = encode(&replay_file, false).unwrap();
+            assert_eq!(bytes[MAGIC.len()], version);
+
+            let decoded = decode(&bytes).unwrap();
+            assert_eq!(
+                serde_json::to_value(&decoded.clicks).unwrap(),
+                serde_json::to_value(&replay_file.clicks).unwrap()
+            );
+        }
+    }
+}
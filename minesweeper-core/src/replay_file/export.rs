@@ -0,0 +1,333 @@
+use std::fmt::{self, Write};
+
+use super::ReplayFile;
+use crate::{
+    click::{ChordButtons, Click},
+    field_generator::replay_field_generator::ReplayFieldGenerator,
+    fields::Fields,
+    mouse_event::{MouseAction, MouseButton, MouseEvent},
+    position::Position,
+    stats::Stats,
+    visibility::Visibility,
+};
+
+const CELL_PIXELS: f32 = 16.0;
+
+fn level(replay_file: &ReplayFile) -> Option<(&'static str, u8)> {
+    match (replay_file.size, replay_file.mines.len()) {
+        ((8, 8), 10) => Some(("Beginner", 3)),
+        ((16, 16), 40) => Some(("Intermediate", 4)),
+        ((30, 16), 99) => Some(("Expert", 5)),
+        _ => None,
+    }
+}
+
+// Other programs don't chord with a single click and don't flag around numbers, so the buttons are
+// pressed the way those would need them, while the cursor keeps following the recorded path.
+fn mouse_events(replay_file: &ReplayFile) -> Vec<MouseEvent> {
+    let size = replay_file.size;
+    let mut fields = Fields::new(
+        &ReplayFieldGenerator::new(replay_file.mines.clone()),
+        size,
+        replay_file.mines.len(),
+        &Position { x: 0, y: 0 },
+    );
+
+    let mut events: Vec<MouseEvent> = replay_file
+        .mouse_events
+        .iter()
+        .filter(|event| event.action == MouseAction::Move)
+        .copied()
+        .collect();
+
+    for click in &replay_file.clicks {
+        let position = click.position();
+        let duration = click.duration();
+        let press = |events: &mut Vec<MouseEvent>, buttons: &[MouseButton], position: Position| {
+            let (x, y) = (position.x as f32 + 0.5, position.y as f32 + 0.5);
+            for button in buttons {
+                events.push(MouseEvent::new(MouseAction::Down(*button), x, y, duration));
+            }
+            for button in buttons.iter().rev() {
+                events.push(MouseEvent::new(MouseAction::Up(*button), x, y, duration));
+            }
+        };
+
+        match click {
+            Click::Reveal(..) => {
+                if fields[&position].is_open() {
+                    press(
+                        &mut events,
+                        &[MouseButton::Left, MouseButton::Right],
+                        position,
+                    );
+                } else {
+                    press(&mut events, &[MouseButton::Left], position);
+                }
+                fields.reveal(&position, true);
+            }
+            Click::Flag(..) => {
+                // Every cell that changed takes a right click, including a question mark that
+                // went back to closed.
+                let visibilities: Vec<Visibility> = fields
+                    .get_fields()
+                    .iter()
+                    .map(|field| field.get_visibility())
+                    .collect();
+                fields.flag(&position);
+
+                for (index, field) in fields.get_fields().iter().enumerate() {
+                    if field.get_visibility() != visibilities[index] {
+                        press(
+                            &mut events,
+                            &[MouseButton::Right],
+                            Position::from_index(index, size),
+                        );
+                    }
+                }
+            }
+            Click::Question(..) => {
+                press(&mut events, &[MouseButton::Right], position);
+                fields.question(&position);
+            }
+            Click::Chord(_, _, buttons) => {
+                if *buttons == ChordButtons::Middle {
+                    press(&mut events, &[MouseButton::Middle], position);
+                } else {
+                    press(
+                        &mut events,
+                        &[MouseButton::Left, MouseButton::Right],
+                        position,
+                    );
+                }
+                fields.chord(&position);
+            }
+        }
+        fields.check_won();
+    }
+
+    events.sort_by_key(|event| event.duration);
+    events
+}
+
+fn pixels(coordinate: f32) -> u16 {
+    (coordinate * CELL_PIXELS).clamp(0.0, u16::MAX as f32) as u16
+}
+
+/* #region RAW */
+
+pub(crate) fn raw(replay_file: &ReplayFile) -> Result<String, fmt::Error> {
+    let stats = replay_file
+        .stats
+        .unwrap_or_else(|| Stats::from_replay(replay_file));
+    let mut raw = String::new();
+
+    writeln!(raw, "RawVF_Version: Rev5")?;
+    writeln!(raw, "Program: Minesweeper")?;
+    writeln!(
+        raw,
+        "Level: {}",
+        level(replay_file).map_or("Custom", |(name, _)| name)
+    )?;
+    writeln!(raw, "Width: {}", replay_file.size.0)?;
+    writeln!(raw, "Height: {}", replay_file.size.1)?;
+    writeln!(raw, "Mines: {}", replay_file.mines.len())?;
+    writeln!(
+        raw,
+        "Marks: {}",
+        if replay_file
+            .clicks
+            .iter()
+            .any(|click| matches!(click, Click::Question(..)))
+        {
+            "On"
+        } else {
+            "Off"
+        }
+    )?;
+    writeln!(raw, "Mode: Classic")?;
+    writeln!(raw, "Time: {:.3}", replay_file.duration.as_secs_f64())?;
+    writeln!(raw, "Result: {}", if stats.won { "Won" } else { "Lost" })?;
+    writeln!(raw, "BBBV: {}", stats.bbbv)?;
+    writeln!(raw, "Solved_BBBV: {}", stats.solved_bbbv)?;
+    writeln!(raw, "BBBV_s: {:.3}", stats.bbbv_per_second())?;
+    writeln!(raw, "Clicks: {}", stats.clicks())?;
+    writeln!(raw, "LClicks: {}", stats.left.total)?;
+    writeln!(raw, "RClicks: {}", stats.right.total)?;
+    writeln!(raw, "DClicks: {}", stats.chord.total)?;
+    writeln!(raw, "Wasted_Clicks: {}", stats.wasted_clicks())?;
+    writeln!(raw, "IOE: {:.3}", stats.ioe())?;
+    writeln!(raw, "Throughput: {:.3}", stats.throughput())?;
+    writeln!(raw, "Correctness: {:.3}", stats.correctness())?;
+    if let Some(seed) = replay_file.seed {
+        writeln!(raw, "Seed: {}", seed)?;
+    }
+
+    writeln!(raw, "Board:")?;
+    for y in 0..replay_file.size.1 {
+        let row: String = (0..replay_file.size.0)
+            .map(|x| {
+                if replay_file
+                    .mines
+                    .iter()
+                    .any(|mine| mine.x == x && mine.y == y)
+                {
+                    '*'
+                } else {
+                    '0'
+                }
+            })
+            .collect();
+        writeln!(raw, "{}", row)?;
+    }
+
+    writeln!(raw, "Events:")?;
+    writeln!(raw, "0.000 start")?;
+    for event in mouse_events(replay_file) {
+        let name = match event.action {
+            MouseAction::Move => "mv",
+            MouseAction::Down(MouseButton::Left) => "lc",
+            MouseAction::Up(MouseButton::Left) => "lr",
+            MouseAction::Down(MouseButton::Right) => "rc",
+            MouseAction::Up(MouseButton::Right) => "rr",
+            MouseAction::Down(MouseButton::Middle) => "mc",
+            MouseAction::Up(MouseButton::Middle) => "mr",
+        };
+        let cell = match event.position(replay_file.size) {
+            Some(position) => format!("{} {}", position.x + 1, position.y + 1),
+            None => "- -".to_string(),
+        };
+
+        writeln!(
+            raw,
+            "{:.3} {} {} ({} {})",
+            event.duration.as_secs_f64(),
+            name,
+            cell,
+            pixels(event.x),
+            pixels(event.y)
+        )?;
+    }
+    writeln!(
+        raw,
+        "{:.3} {}",
+        replay_file.duration.as_secs_f64(),
+        if stats.won { "won" } else { "blast" }
+    )?;
+
+    Ok(raw)
+}
+
+/* #endregion */
+
+/* #region Minesweeper Arbiter */
+
+pub(crate) fn avf(replay_file: &ReplayFile) -> Result<Vec<u8>, String> {
+    let size = replay_file.size;
+    let mut bytes = vec![0, 0, 0, 0, 0];
+
+    if size.0 > u8::MAX as usize || size.1 > u8::MAX as usize {
+        return Err("Board is too big for an AVF file".to_string());
+    }
+
+    match level(replay_file) {
+        Some((_, mode)) => bytes.push(mode),
+        None => {
+            bytes.extend([6, size.0 as u8 - 1, size.1 as u8 - 1]);
+            bytes.extend((replay_file.mines.len() as u16).to_be_bytes());
+        }
+    }
+
+    for mine in &replay_file.mines {
+        bytes.extend([mine.y as u8 + 1, mine.x as u8 + 1]);
+    }
+
+    bytes.extend(
+        format!(
+            "[0|{:.3}|{}x{}|{}]",
+            replay_file.duration.as_secs_f64(),
+            size.0,
+            size.1,
+            replay_file.mines.len()
+        )
+        .as_bytes(),
+    );
+
+    for event in mouse_events(replay_file) {
+        let event_type = match event.action {
+            MouseAction::Move => 1,
+            MouseAction::Down(MouseButton::Left) => 3,
+            MouseAction::Up(MouseButton::Left) => 5,
+            MouseAction::Down(MouseButton::Right) => 9,
+            MouseAction::Up(MouseButton::Right) => 17,
+            MouseAction::Down(MouseButton::Middle) => 33,
+            MouseAction::Up(MouseButton::Middle) => 65,
+        };
+
+        // Seconds are stored off by one, with the hundredths in a separate byte.
+        let seconds = u16::try_from(event.duration.as_secs() + 1)
+            .map_err(|_| "Game is too long for an AVF file".to_string())?
+            .to_be_bytes();
+        let hundredths = (event.duration.subsec_millis() / 10) as u8;
+        let x = pixels(event.x).to_be_bytes();
+        let y = pixels(event.y).to_be_bytes();
+
+        bytes.extend([
+            event_type, x[0], seconds[1], x[1], hundredths, y[0], seconds[0], y[1],
+        ]);
+    }
+    bytes.extend([0; 8]);
+
+    Ok(bytes)
+}
+
+/* #endregion */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay_file::{tests::won_game, ReplayFormat};
+
+    #[test]
+    fn avf_round_trip() {
+        let replay_file = won_game();
+
+        let imported = ReplayFile::from_bytes(
+            &replay_file.to_bytes(ReplayFormat::Avf).unwrap(),
+            ReplayFormat::Avf,
+        )
+        .unwrap();
+
+        assert_eq!(imported.size, replay_file.size);
+        assert_eq!(
+            serde_json::to_value(&imported.mines).unwrap(),
+            serde_json::to_value(&replay_file.mines).unwrap()
+        );
+        // AVF keeps time in hundredths of a second, which the clicks here are already rounded to.
+        assert_eq!(
+            serde_json::to_value(&imported.clicks).unwrap(),
+            serde_json::to_value(&replay_file.clicks).unwrap()
+        );
+        assert!(imported.stats.unwrap().won);
+    }
+
+    #[test]
+    fn flagging_a_question_mark_presses_right() {
+        let mut replay_file = won_game();
+        let Click::Flag(mine, time) = replay_file.clicks[1] else {
+            panic!("The second click should be a flag");
+        };
+        replay_file
+            .clicks
+            .splice(2..2, [Click::Question(mine, time), Click::Flag(mine, time)]);
+
+        let right_presses = mouse_events(&replay_file)
+            .iter()
+            .filter(|event| {
+                event.action == MouseAction::Down(MouseButton::Right)
+                    && event.position(replay_file.size) == Some(mine)
+            })
+            .count();
+        assert_eq!(right_presses, 3);
+    }
+}
@@ -0,0 +1,516 @@
+use std::time::Duration;
+
+use super::{ReplayFile, REPLAY_FILE_VERSION};
+use crate::{
+    click::{ChordButtons, Click},
+    field_generator::replay_field_generator::ReplayFieldGenerator,
+    fields::Fields,
+    mouse_event::{MouseAction, MouseButton, MouseEvent},
+    position::Position,
+    tile::Tile,
+    visibility::Visibility,
+};
+
+// Both formats store the cursor in pixels of the original 16x16 skin.
+const CELL_PIXELS: f32 = 16.0;
+
+const RMV_BOARD_LEFT: i32 = 12;
+const RMV_BOARD_TOP: i32 = 56;
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    format: &'static str,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8], format: &'static str) -> Self {
+        Self {
+            bytes,
+            offset: 0,
+            format,
+        }
+    }
+
+    fn take(&mut self, amount: usize) -> Result<&'a [u8], String> {
+        if self.bytes.len() - self.offset < amount {
+            return Err(format!(
+                "Unexpected end of {} file at byte {}",
+                self.format, self.offset
+            ));
+        }
+
+        self.offset += amount;
+        Ok(&self.bytes[self.offset - amount..self.offset])
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u24(&mut self) -> Result<u32, String> {
+        let bytes = self.take(3)?;
+        Ok(u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn skip(&mut self, amount: usize) -> Result<(), String> {
+        self.take(amount).map(|_| ())
+    }
+}
+
+/* #region Minesweeper Arbiter */
+
+pub(crate) fn avf(bytes: &[u8]) -> Result<ReplayFile, String> {
+    let mut reader = Reader::new(bytes, "AVF");
+
+    let _version = reader.u8()?;
+    reader.skip(4)?;
+
+    let (size, mine_amount) = match reader.u8()? {
+        3 => ((8, 8), 10),
+        4 => ((16, 16), 40),
+        5 => ((30, 16), 99),
+        6 => {
+            let width = reader.u8()? as usize + 1;
+            let height = reader.u8()? as usize + 1;
+            ((width, height), reader.u16()? as usize)
+        }
+        mode => return Err(format!("Unsupported AVF game mode: {}", mode)),
+    };
+
+    let mut mines = Vec::with_capacity(mine_amount);
+    for _ in 0..mine_amount {
+        let y = reader.u8()? as usize;
+        let x = reader.u8()? as usize;
+        mines.push(
+            x.checked_sub(1)
+                .zip(y.checked_sub(1))
+                .and_then(|(x, y)| Position::new(x, y, size))
+                .ok_or_else(|| format!("AVF mine out of bounds: {}, {}", x, y))?,
+        );
+    }
+
+    // The mines are followed by a free-form info section, so instead of parsing it we look for the
+    // first event, which always happens in the first second.
+    let start = (reader.offset..bytes.len().saturating_sub(7))
+        .find(|&offset| {
+            let event = &bytes[offset..offset + 8];
+            avf_action(event[0]).is_some()
+                && event[1] <= 1
+                && event[2] == 1
+                && event[5] <= 1
+                && event[6] == 0
+        })
+        .ok_or_else(|| "Couldn't find the first event in AVF file".to_string())?;
+    reader.offset = start;
+
+    let mut events = Vec::new();
+    while reader.bytes.len() - reader.offset >= 8 {
+        let event = reader.take(8)?;
+
+        // Seconds are stored off by one, so zero marks the end of the events.
+        let seconds = u16::from_be_bytes([event[6], event[2]]);
+        if seconds == 0 {
+            break;
+        }
+
+        let action = avf_action(event[0]).ok_or_else(|| {
+            format!(
+                "Unsupported AVF event type {} at byte {}",
+                event[0],
+                reader.offset - 8
+            )
+        })?;
+
+        events.push(MouseEvent::new(
+            action,
+            u16::from_be_bytes([event[1], event[3]]) as f32 / CELL_PIXELS,
+            u16::from_be_bytes([event[5], event[7]]) as f32 / CELL_PIXELS,
+            Duration::from_secs(seconds as u64 - 1) + Duration::from_millis(event[4] as u64 * 10),
+        ));
+    }
+
+    convert(mines, size, events, "Minesweeper Arbiter")
+}
+
+fn avf_action(event_type: u8) -> Option<MouseAction> {
+    match event_type {
+        1 => Some(MouseAction::Move),
+        3 | 11 => Some(MouseAction::Down(MouseButton::Left)),
+        5 | 21 => Some(MouseAction::Up(MouseButton::Left)),
+        9 => Some(MouseAction::Down(MouseButton::Right)),
+        17 | 145 => Some(MouseAction::Up(MouseButton::Right)),
+        33 => Some(MouseAction::Down(MouseButton::Middle)),
+        65 | 193 => Some(MouseAction::Up(MouseButton::Middle)),
+        _ => None,
+    }
+}
+
+/* #endregion */
+
+/* #region Viennasweeper */
+
+pub(crate) fn rmv(bytes: &[u8]) -> Result<ReplayFile, String> {
+    let mut reader = Reader::new(bytes, "RMV");
+
+    if reader.take(4)? != b"*rmv" {
+        return Err("Not an RMV file".to_string());
+    }
+
+    let format_version = reader.u16()?;
+    if format_version != 1 {
+        return Err(format!(
+            "Unsupported RMV format version: {}",
+            format_version
+        ));
+    }
+
+    let result_string_size = reader.u16()? as usize;
+    let version_info_size = reader.u16()? as usize;
+    let player_info_size = reader.u16()? as usize;
+    let board_size = reader.u16()? as usize;
+    let preflags_size = reader.u16()? as usize;
+    let properties_size = reader.u16()? as usize;
+    let events_size = reader.u32()? as usize;
+    let _checksum_size = reader.u16()?;
+
+    reader.skip(result_string_size + version_info_size + player_info_size)?;
+
+    let board = reader.take(board_size)?;
+    let (size, mines) = rmv_board(board)?;
+
+    if preflags_size > 0 {
+        return Err("RMV files with pre-placed flags aren't supported".to_string());
+    }
+
+    let properties = reader.take(properties_size)?;
+    if let Some(&mode) = properties.get(2) {
+        if mode != 0 {
+            return Err(format!(
+                "Unsupported RMV game mode: {}",
+                match mode {
+                    1 => "UPK".to_string(),
+                    2 => "Cheat".to_string(),
+                    3 => "Density".to_string(),
+                    mode => mode.to_string(),
+                }
+            ));
+        }
+    }
+
+    let mut reader = Reader::new(reader.take(events_size)?, "RMV");
+    let mut events = Vec::new();
+    while reader.offset < reader.bytes.len() {
+        let event_type = reader.u8()?;
+        let action = match event_type {
+            1 => MouseAction::Move,
+            2 => MouseAction::Down(MouseButton::Left),
+            3 => MouseAction::Up(MouseButton::Left),
+            4 => MouseAction::Down(MouseButton::Right),
+            5 => MouseAction::Up(MouseButton::Right),
+            6 => MouseAction::Down(MouseButton::Middle),
+            7 => MouseAction::Up(MouseButton::Middle),
+            9..=14 | 18..=27 => {
+                // Board events, which we recompute ourselves.
+                reader.skip(2)?;
+                continue;
+            }
+            15..=17 => break,
+            event_type => {
+                return Err(format!(
+                    "Unsupported RMV event type {} at byte {}",
+                    event_type,
+                    reader.offset - 1
+                ))
+            }
+        };
+
+        let time = reader.u24()?;
+        let x = reader.u16()? as i32 - RMV_BOARD_LEFT;
+        let y = reader.u16()? as i32 - RMV_BOARD_TOP;
+
+        events.push(MouseEvent::new(
+            action,
+            x as f32 / CELL_PIXELS,
+            y as f32 / CELL_PIXELS,
+            Duration::from_millis(time as u64),
+        ));
+    }
+
+    convert(mines, size, events, "Viennasweeper")
+}
+
+fn rmv_board(board: &[u8]) -> Result<((usize, usize), Vec<Position>), String> {
+    // Some versions put a 4 byte header in front of the board, the size tells us which one this is.
+    for header in [4, 0] {
+        let Some(&[width, height, mines_high, mines_low]) = board.get(header..header + 4) else {
+            continue;
+        };
+        let mine_amount = u16::from_be_bytes([mines_high, mines_low]) as usize;
+        if board.len() != header + 4 + mine_amount * 2 {
+            continue;
+        }
+
+        let size = (width as usize, height as usize);
+        let mines = board[header + 4..]
+            .chunks(2)
+            .map(|mine| {
+                Position::new(mine[0] as usize, mine[1] as usize, size)
+                    .ok_or_else(|| format!("RMV mine out of bounds: {}, {}", mine[0], mine[1]))
+            })
+            .collect::<Result<_, _>>()?;
+
+        return Ok((size, mines));
+    }
+
+    Err("Couldn't read the board of RMV file".to_string())
+}
+
+/* #endregion */
+
+// Turns raw mouse events into the clicks this game would have made, following the usual rules:
+// left release opens, right press flags and releasing a button while the other one is held (or
+// releasing the middle button) chords.
+fn convert(
+    mines: Vec<Position>,
+    size: (usize, usize),
+    mouse_events: Vec<MouseEvent>,
+    imported_from: &str,
+) -> Result<ReplayFile, String> {
+    let mut fields = Fields::new(
+        &ReplayFieldGenerator::new(mines.clone()),
+        size,
+        mines.len(),
+        &Position { x: 0, y: 0 },
+    );
+
+    let mut clicks = Vec::new();
+    let (mut left, mut right, mut chorded) = (false, false, false);
+    for event in &mouse_events {
+        if !fields.won.still_playing() {
+            break;
+        }
+
+        let position = event.position(size);
+        let opens = match event.action {
+            MouseAction::Move => false,
+            MouseAction::Down(MouseButton::Left) => {
+                left = true;
+                false
+            }
+            MouseAction::Down(MouseButton::Right) => {
+                right = true;
+                if !left && !clicks.is_empty() {
+                    if let Some(position) = position.filter(|position| !fields[position].is_open())
+                    {
+                        fields.flag(&position);
+                        clicks.push(Click::Flag(position, event.duration));
+                    }
+                }
+                false
+            }
+            MouseAction::Down(MouseButton::Middle) => false,
+            MouseAction::Up(MouseButton::Left) => {
+                left = false;
+                let opens = right || !chorded;
+                chorded |= right;
+                opens
+            }
+            MouseAction::Up(MouseButton::Right) => {
+                right = false;
+                let opens = left;
+                chorded |= left;
+                opens
+            }
+            MouseAction::Up(MouseButton::Middle) => true,
+        };
+        if !left && !right {
+            chorded = false;
+        }
+
+        let Some(position) = position.filter(|_| opens) else {
+            continue;
+        };
+
+        let middle = event.action == MouseAction::Up(MouseButton::Middle);
+        let chord = left || right || middle;
+        let reveal = match (
+            fields[&position].get_visibility(),
+            fields[&position].get_tile(),
+        ) {
+            (Visibility::Closed, _) => !chord,
+            (Visibility::Open, Tile::Number(_)) => chord,
+            _ => false,
+        };
+
+        if reveal && chord {
+            fields.chord(&position);
+            fields.check_won();
+            clicks.push(Click::Chord(
+                position,
+                event.duration,
+                if middle {
+                    ChordButtons::Middle
+                } else {
+                    ChordButtons::LeftRight
+                },
+            ));
+        } else if reveal {
+            fields.reveal(&position, true);
+            fields.check_won();
+            clicks.push(Click::Reveal(position, event.duration));
+        }
+    }
+
+    // The timer starts with the first opened cell, flags placed before that are ignored.
+    let Some(start) = clicks.first().map(|click| click.duration()) else {
+        return Err(format!(
+            "No cells were opened in this {} game",
+            imported_from
+        ));
+    };
+
+    let clicks: Vec<Click> = clicks
+        .into_iter()
+        .map(|click| match click {
+            Click::Reveal(position, duration) => {
+                Click::Reveal(position, duration.saturating_sub(start))
+            }
+            Click::Flag(position, duration) => {
+                Click::Flag(position, duration.saturating_sub(start))
+            }
+            Click::Question(position, duration) => {
+                Click::Question(position, duration.saturating_sub(start))
+            }
+            Click::Chord(position, duration, buttons) => {
+                Click::Chord(position, duration.saturating_sub(start), buttons)
+            }
+        })
+        .collect();
+    let duration = clicks.last().unwrap().duration();
+
+    let mouse_events = mouse_events
+        .into_iter()
+        .filter(|event| event.duration >= start && event.duration - start <= duration)
+        .map(|event| MouseEvent {
+            duration: event.duration - start,
+            ..event
+        })
+        .collect();
+
+    Ok(ReplayFile {
+        version: REPLAY_FILE_VERSION,
+        mines,
+        size,
+        clicks,
+        duration,
+        seed: None,
+        stats: None,
+        mouse_events,
+        imported_from: Some(imported_from.to_string()),
+        signature: None,
+        metadata: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay_file::ReplayFormat;
+
+    fn coordinates(positions: &[Position]) -> Vec<(usize, usize)> {
+        let mut coordinates: Vec<_> = positions.iter().map(|pos| (pos.x, pos.y)).collect();
+        coordinates.sort_unstable();
+        coordinates
+    }
+
+    // What each click was, where, and how many milliseconds after the first reveal.
+    fn clicks(replay_file: &ReplayFile) -> Vec<(&'static str, (usize, usize), u128)> {
+        replay_file
+            .clicks
+            .iter()
+            .map(|click| {
+                let kind = match click {
+                    Click::Reveal(..) => "reveal",
+                    Click::Flag(..) => "flag",
+                    Click::Question(..) => "question",
+                    Click::Chord(_, _, ChordButtons::Left) => "left chord",
+                    Click::Chord(_, _, ChordButtons::LeftRight) => "left right chord",
+                    Click::Chord(_, _, ChordButtons::Middle) => "middle chord",
+                };
+                let pos = click.position();
+                (kind, (pos.x, pos.y), click.duration().as_millis())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn avf() {
+        let replay_file = ReplayFile::from_bytes(
+            include_bytes!("../../test-data/beginner_won.avf"),
+            ReplayFormat::Avf,
+        )
+        .unwrap();
+
+        assert_eq!(replay_file.size, (8, 8));
+        let mut mines: Vec<_> = (0..8).map(|y| (3, y)).collect();
+        mines.extend([(0, 0), (7, 7)]);
+        mines.sort_unstable();
+        assert_eq!(coordinates(&replay_file.mines), mines);
+
+        assert_eq!(
+            clicks(&replay_file),
+            [
+                ("reveal", (1, 1), 0),
+                ("flag", (0, 0), 250),
+                ("middle chord", (1, 1), 600),
+                ("left right chord", (2, 3), 1050),
+                ("reveal", (6, 0), 1540),
+            ]
+        );
+        assert!(replay_file.stats.unwrap().won);
+        assert_eq!(
+            replay_file.imported_from.as_deref(),
+            Some("Minesweeper Arbiter")
+        );
+        // The move and press before the first reveal are dropped.
+        assert_eq!(replay_file.mouse_events.len(), 15);
+    }
+
+    #[test]
+    fn rmv() {
+        let replay_file = ReplayFile::from_bytes(
+            include_bytes!("../../test-data/beginner_lost.rmv"),
+            ReplayFormat::Rmv,
+        )
+        .unwrap();
+
+        assert_eq!(replay_file.size, (8, 8));
+        let mut mines: Vec<_> = (0..8).map(|y| (4, y)).collect();
+        mines.extend([(7, 0), (6, 7)]);
+        mines.sort_unstable();
+        assert_eq!(coordinates(&replay_file.mines), mines);
+
+        assert_eq!(
+            clicks(&replay_file),
+            [
+                ("reveal", (0, 0), 0),
+                ("flag", (4, 0), 220),
+                ("left right chord", (3, 0), 620),
+                ("reveal", (7, 0), 970),
+            ]
+        );
+        assert!(!replay_file.stats.unwrap().won);
+        assert_eq!(replay_file.imported_from.as_deref(), Some("Viennasweeper"));
+    }
+}
@@ -0,0 +1,69 @@
+use serde_derive::{Deserialize, Serialize};
+
+use super::ReplayFile;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Generator {
+    Random,
+    Seeded,
+    NoGuess,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Flagging {
+    Flags,
+    NoFlags,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum GameResult {
+    Won,
+    Lost,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Metadata {
+    pub player: String,
+    // Seconds since the Unix epoch (UTC) of the first click.
+    pub started_at: u64,
+    pub game_version: String,
+    pub generator: Generator,
+    pub flagging: Flagging,
+    pub result: GameResult,
+    // Played in practice mode, where moves can be taken back.
+    pub assisted: bool,
+    // The mine count the game was set up with, older replays don't have it.
+    #[serde(default)]
+    pub mine_amount: Option<usize>,
+}
+
+impl Metadata {
+    pub fn new(
+        player: String,
+        started_at: u64,
+        game_version: String,
+        generator: Generator,
+        assisted: bool,
+        mine_amount: usize,
+        replay_file: &ReplayFile,
+    ) -> Self {
+        Self {
+            player,
+            started_at,
+            game_version,
+            generator,
+            flagging: if replay_file.flagged() {
+                Flagging::Flags
+            } else {
+                Flagging::NoFlags
+            },
+            result: if replay_file.stats.is_some_and(|stats| stats.won) {
+                GameResult::Won
+            } else {
+                GameResult::Lost
+            },
+            assisted,
+            mine_amount: Some(mine_amount),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use serde_json::{Map, Value};
+
+use super::REPLAY_FILE_VERSION;
+
+type Migration = fn(&mut Map<String, Value>) -> Result<(), String>;
+
+// MIGRATIONS[n] turns a version n file into a version n + 1 file. Later versions only added new
+// kinds of clicks and the declared mine count, which older files just don't have.
+const MIGRATIONS: &[Migration] = &[
+    from_unversioned,
+    add_mouse_events,
+    add_imported_from,
+    add_signature,
+    add_metadata,
+    add_assisted,
+];
+
+pub(crate) fn migrate(mut value: Value) -> Result<Value, String> {
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| "Replay file isn't a JSON object".to_string())?;
+
+    let version = match object.get("version") {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| format!("Invalid replay file version: {}", version))?,
+    };
+
+    if version > REPLAY_FILE_VERSION as u64 {
+        return Err(newer_version_error(version));
+    }
+
+    for migration in MIGRATIONS.iter().skip(version as usize) {
+        migration(object)?;
+    }
+    object.insert("version".to_string(), Value::from(REPLAY_FILE_VERSION));
+
+    Ok(value)
+}
+
+pub(crate) fn newer_version_error(version: u64) -> String {
+    format!(
+        "Replay file version {} is newer than the supported version {}, update the game to open it",
+        version, REPLAY_FILE_VERSION
+    )
+}
+
+fn from_unversioned(object: &mut Map<String, Value>) -> Result<(), String> {
+    for key in ["mines", "size", "clicks", "duration"] {
+        if !object.contains_key(key) {
+            return Err(format!("Replay file is missing \"{}\"", key));
+        }
+    }
+
+    Ok(())
+}
+
+fn add_mouse_events(object: &mut Map<String, Value>) -> Result<(), String> {
+    object
+        .entry("mouse_events")
+        .or_insert_with(|| Value::Array(Vec::new()));
+
+    Ok(())
+}
+
+fn add_imported_from(object: &mut Map<String, Value>) -> Result<(), String> {
+    object.entry("imported_from").or_insert(Value::Null);
+
+    Ok(())
+}
+
+fn add_signature(object: &mut Map<String, Value>) -> Result<(), String> {
+    object.entry("signature").or_insert(Value::Null);
+
+    Ok(())
+}
+
+fn add_metadata(object: &mut Map<String, Value>) -> Result<(), String> {
+    object.entry("metadata").or_insert(Value::Null);
+
+    Ok(())
+}
+
+fn add_assisted(object: &mut Map<String, Value>) -> Result<(), String> {
+    if let Some(Value::Object(metadata)) = object.get_mut("metadata") {
+        metadata.entry("assisted").or_insert(Value::Bool(false));
+    }
+
+    Ok(())
+}
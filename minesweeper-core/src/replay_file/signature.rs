@@ -0,0 +1,195 @@
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde_derive::{Deserialize, Serialize};
+
+use super::{
+    metadata::{Flagging, GameResult, Generator},
+    ReplayFile,
+};
+use crate::click::{ChordButtons, Click};
+
+const DOMAIN: &[u8] = b"minesweeper replay signature v1";
+
+pub const PUBLIC_KEY_LENGTH: usize = ed25519_dalek::PUBLIC_KEY_LENGTH;
+pub const SIGNATURE_LENGTH: usize = ed25519_dalek::SIGNATURE_LENGTH;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ReplaySignature {
+    pub public_key: String,
+    pub signature: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SignatureStatus {
+    Unsigned,
+    Signed,
+    // Intact, but not signed with any of the trusted keys, so anyone could have made it.
+    OtherKey,
+    Tampered,
+}
+
+impl ReplaySignature {
+    pub(crate) fn from_bytes(
+        public_key: &[u8; PUBLIC_KEY_LENGTH],
+        signature: &[u8; SIGNATURE_LENGTH],
+    ) -> Self {
+        Self {
+            public_key: to_hex(public_key),
+            signature: to_hex(signature),
+        }
+    }
+
+    pub(crate) fn to_bytes(&self) -> Option<([u8; PUBLIC_KEY_LENGTH], [u8; SIGNATURE_LENGTH])> {
+        Some((
+            from_hex(&self.public_key)?.try_into().ok()?,
+            from_hex(&self.signature)?.try_into().ok()?,
+        ))
+    }
+}
+
+pub fn public_key(secret_key: &[u8; 32]) -> [u8; PUBLIC_KEY_LENGTH] {
+    SigningKey::from_bytes(secret_key)
+        .verifying_key()
+        .to_bytes()
+}
+
+pub(crate) fn sign(replay_file: &ReplayFile, secret_key: &[u8; 32]) -> ReplaySignature {
+    let signing_key = SigningKey::from_bytes(secret_key);
+    let signature = signing_key.sign(&canonical_bytes(replay_file));
+
+    ReplaySignature::from_bytes(
+        signing_key.verifying_key().as_bytes(),
+        &signature.to_bytes(),
+    )
+}
+
+pub(crate) fn verify(
+    replay_file: &ReplayFile,
+    trusted_keys: &[[u8; PUBLIC_KEY_LENGTH]],
+) -> SignatureStatus {
+    let Some(signature) = &replay_file.signature else {
+        return SignatureStatus::Unsigned;
+    };
+    let Some((public_key, signature)) = signature.to_bytes() else {
+        return SignatureStatus::Tampered;
+    };
+
+    let valid = VerifyingKey::from_bytes(&public_key).is_ok_and(|key| {
+        key.verify(
+            &canonical_bytes(replay_file),
+            &Signature::from_bytes(&signature),
+        )
+        .is_ok()
+    });
+
+    if !valid {
+        SignatureStatus::Tampered
+    } else if trusted_keys.contains(&public_key) {
+        SignatureStatus::Signed
+    } else {
+        SignatureStatus::OtherKey
+    }
+}
+
+// The game and its metadata, but none of the derived stats or the mouse path, so converting
+// between our formats keeps the signature.
+fn canonical_bytes(replay_file: &ReplayFile) -> Vec<u8> {
+    let size = replay_file.size;
+    let mut bytes = DOMAIN.to_vec();
+
+    bytes.extend((size.0 as u64).to_le_bytes());
+    bytes.extend((size.1 as u64).to_le_bytes());
+
+    let mut mines: Vec<usize> = replay_file
+        .mines
+        .iter()
+        .map(|mine| mine.index(size))
+        .collect();
+    mines.sort_unstable();
+    bytes.extend((mines.len() as u64).to_le_bytes());
+    for mine in mines {
+        bytes.extend((mine as u64).to_le_bytes());
+    }
+
+    bytes.extend((replay_file.clicks.len() as u64).to_le_bytes());
+    for click in &replay_file.clicks {
+        bytes.push(match click {
+            Click::Reveal(..) => 0,
+            Click::Flag(..) => 1,
+            Click::Question(..) => 2,
+            Click::Chord(_, _, ChordButtons::Left) => 3,
+            Click::Chord(_, _, ChordButtons::LeftRight) => 4,
+            Click::Chord(_, _, ChordButtons::Middle) => 5,
+        });
+        bytes.extend((click.position().index(size) as u64).to_le_bytes());
+        bytes.extend((click.duration().as_nanos() as u64).to_le_bytes());
+    }
+
+    bytes.extend((replay_file.duration.as_nanos() as u64).to_le_bytes());
+
+    if let Some(metadata) = &replay_file.metadata {
+        bytes.extend((metadata.player.len() as u64).to_le_bytes());
+        bytes.extend(metadata.player.as_bytes());
+        bytes.extend(metadata.started_at.to_le_bytes());
+        bytes.extend((metadata.game_version.len() as u64).to_le_bytes());
+        bytes.extend(metadata.game_version.as_bytes());
+        bytes.push(match metadata.generator {
+            Generator::Random => 0,
+            Generator::Seeded => 1,
+            Generator::NoGuess => 2,
+        });
+        bytes.push(match metadata.flagging {
+            Flagging::Flags => 0,
+            Flagging::NoFlags => 1,
+        });
+        bytes.push(match metadata.result {
+            GameResult::Won => 0,
+            GameResult::Lost => 1,
+        });
+        bytes.push(metadata.assisted as u8);
+        if let Some(mine_amount) = metadata.mine_amount {
+            bytes.extend((mine_amount as u64).to_le_bytes());
+        }
+    }
+
+    bytes
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay_file::tests::won_game;
+
+    const KEY: [u8; 32] = [7; 32];
+
+    #[test]
+    fn only_trusted_keys_count_as_signed() {
+        let mut replay_file = won_game();
+        replay_file.sign(&KEY);
+        assert!(replay_file.signature_status(&[public_key(&KEY)]) == SignatureStatus::Signed);
+
+        // An edited replay re-signed with a fresh key is intact, but not ours.
+        replay_file.clicks.pop();
+        replay_file.sign(&[8; 32]);
+        assert!(replay_file.signature_status(&[public_key(&KEY)]) == SignatureStatus::OtherKey);
+    }
+
+    #[test]
+    fn player_name_is_signed() {
+        let mut replay_file = won_game();
+        replay_file.sign(&KEY);
+
+        replay_file.metadata.as_mut().unwrap().player = "Someone else".to_string();
+        assert!(replay_file.signature_status(&[public_key(&KEY)]) == SignatureStatus::Tampered);
+    }
+}
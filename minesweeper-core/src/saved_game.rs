@@ -0,0 +1,143 @@
+use std::{fs, io, path::Path, time::Duration};
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    click::Click, fields::Fields, mouse_event::MouseEvent, replay_file::metadata::Generator,
+};
+
+pub const SAVED_GAME_VERSION: u32 = 1;
+
+// An unfinished game, saved on exit so it can be picked up again on the next launch.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SavedGame {
+    pub version: u32,
+    pub size: (usize, usize),
+    pub mine_amount: usize,
+    pub seed: u64,
+    // Whether the seed was picked by the player, no guessing games can be either. Saves from
+    // before this was kept count as random.
+    #[serde(default)]
+    pub seeded: bool,
+    pub generator: Generator,
+
+    pub fields: Fields,
+    pub clicks: Vec<Click>,
+    pub mouse_events: Vec<MouseEvent>,
+    pub elapsed: Duration,
+    pub started_at: Option<u64>,
+    pub assisted: bool,
+}
+
+impl SavedGame {
+    pub fn load(path: &Path) -> Result<Option<Self>, String> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.to_string()),
+        };
+
+        let saved_game: Self = serde_json::from_str(&contents).map_err(|e| e.to_string())?;
+        if let Some(err) = saved_game.validate() {
+            return Err(err);
+        }
+
+        Ok(Some(saved_game))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+
+        fs::write(
+            path,
+            serde_json::to_string(self).map_err(|e| e.to_string())?,
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    fn validate(&self) -> Option<String> {
+        if self.version != SAVED_GAME_VERSION {
+            return Some(format!("Unsupported saved game version {}", self.version));
+        }
+
+        if self.fields.get_size() != self.size
+            || self.fields.get_fields().len() != self.size.0 * self.size.1
+        {
+            return Some("The board doesn't match the saved size".to_string());
+        }
+
+        if self.fields.get_mines().len() != self.mine_amount {
+            return Some("The board doesn't match the saved mine amount".to_string());
+        }
+
+        if !self.fields.won.still_playing() {
+            return Some("The saved game is already over".to_string());
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::replay_file::tests::won_game;
+
+    fn saved_game() -> SavedGame {
+        let replay_file = won_game();
+        let fields = replay_file.fields_at(2).unwrap();
+
+        SavedGame {
+            version: SAVED_GAME_VERSION,
+            size: replay_file.size,
+            mine_amount: replay_file.mines.len(),
+            seed: 7,
+            seeded: true,
+            generator: Generator::NoGuess,
+
+            fields,
+            clicks: replay_file.clicks[..2].to_vec(),
+            mouse_events: replay_file.mouse_events[..4].to_vec(),
+            elapsed: replay_file.clicks[1].duration(),
+            started_at: Some(1_700_000_000),
+            assisted: false,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let path =
+            env::temp_dir().join(format!("saved_game_round_trip_{}.json", std::process::id()));
+        let saved_game = saved_game();
+
+        saved_game.save(&path).unwrap();
+        let loaded = SavedGame::load(&path).unwrap().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&loaded).unwrap(),
+            serde_json::to_value(&saved_game).unwrap()
+        );
+        assert!(loaded.seeded);
+    }
+
+    #[test]
+    fn rejects_corrupt_saves() {
+        let path = env::temp_dir().join(format!("saved_game_corrupt_{}.json", std::process::id()));
+
+        fs::write(&path, "{\"version\": 1, \"size\": [8,").unwrap();
+        assert!(SavedGame::load(&path).is_err());
+
+        let mut saved_game = saved_game();
+        saved_game.mine_amount += 1;
+        saved_game.save(&path).unwrap();
+        assert!(SavedGame::load(&path).is_err());
+
+        fs::remove_file(&path).unwrap();
+        assert!(SavedGame::load(&path).unwrap().is_none());
+    }
+}
@@ -0,0 +1,639 @@
+use std::collections::HashMap;
+
+use crate::{field::Field, fields::Fields, position::Position, tile::Tile, visibility::Visibility};
+
+const MAX_ENUMERATION_STEPS: usize = 1 << 20;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CellState {
+    Closed,
+    Flagged,
+    Open(usize),
+}
+
+impl From<&Field> for CellState {
+    fn from(field: &Field) -> Self {
+        match (field.get_visibility(), field.get_tile()) {
+            (Visibility::Closed | Visibility::Questioned, _) | (Visibility::Open, Tile::Mine) => {
+                Self::Closed
+            }
+            (Visibility::Flagged, _) => Self::Flagged,
+            (Visibility::Open, Tile::Empty) => Self::Open(0),
+            (Visibility::Open, Tile::Number(num)) => Self::Open(num.into()),
+        }
+    }
+}
+
+pub struct Solution {
+    pub safe: Vec<Position>,
+    pub mines: Vec<Position>,
+    pub probabilities: Vec<Option<f64>>,
+
+    size: (usize, usize),
+    flagged: Vec<bool>,
+}
+
+impl Solution {
+    pub fn probability(&self, position: &Position) -> Option<f64> {
+        self.probabilities[position.index(self.size)]
+    }
+
+    pub fn hint(&self) -> Option<Position> {
+        self.probabilities
+            .iter()
+            .zip(&self.flagged)
+            .enumerate()
+            .filter(|(_, (_, &flagged))| !flagged)
+            .filter_map(|(index, (probability, _))| probability.map(|p| (index, p)))
+            .filter(|&(_, probability)| probability < 1.0)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| Position::from_index(index, self.size))
+    }
+}
+
+struct Constraint {
+    cells: Vec<usize>,
+    mines: usize,
+}
+
+struct Component {
+    cells: Vec<usize>,
+    solutions: Vec<f64>,
+    mine_solutions: Vec<Vec<f64>>,
+}
+
+pub struct Solver {
+    size: (usize, usize),
+    mine_amount: usize,
+}
+
+impl Solver {
+    pub fn new(size: (usize, usize), mine_amount: usize) -> Self {
+        Self { size, mine_amount }
+    }
+
+    pub fn solve_fields(fields: &Fields) -> Solution {
+        Self::new(fields.get_size(), fields.get_mine_amount()).solve(
+            &fields
+                .get_fields()
+                .iter()
+                .map(CellState::from)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    pub fn solve(&self, cells: &[CellState]) -> Solution {
+        self.deduce(cells, true)
+    }
+
+    pub(crate) fn solve_lazily(&self, cells: &[CellState]) -> Solution {
+        self.deduce(cells, false)
+    }
+
+    fn deduce(&self, cells: &[CellState], exhaustive: bool) -> Solution {
+        let mut known: Vec<Option<bool>> = cells
+            .iter()
+            .map(|cell| match cell {
+                CellState::Open(_) => Some(false),
+                CellState::Closed | CellState::Flagged => None,
+            })
+            .collect();
+
+        while Self::apply_simple_rules(&self.constraints(cells, &known), &mut known) {}
+
+        let unknown = known.iter().filter(|cell| cell.is_none()).count();
+        let known_mines = known.iter().filter(|&&cell| cell == Some(true)).count();
+        let mut probabilities = vec![
+            self.mine_amount.saturating_sub(known_mines) as f64
+                / unknown.max(1) as f64;
+            cells.len()
+        ];
+
+        if exhaustive
+            || !known
+                .iter()
+                .zip(cells)
+                .any(|(cell, state)| *cell == Some(false) && !matches!(state, CellState::Open(_)))
+        {
+            self.apply_enumeration(
+                &self.constraints(cells, &known),
+                &mut known,
+                &mut probabilities,
+            );
+        }
+
+        let mut solution = Solution {
+            safe: Vec::new(),
+            mines: Vec::new(),
+            probabilities: Vec::with_capacity(cells.len()),
+
+            size: self.size,
+            flagged: cells
+                .iter()
+                .map(|cell| *cell == CellState::Flagged)
+                .collect(),
+        };
+
+        for (index, cell) in known.iter().enumerate() {
+            let open = matches!(cells[index], CellState::Open(_));
+            match cell {
+                Some(false) if !open => solution.safe.push(Position::from_index(index, self.size)),
+                Some(true) => solution.mines.push(Position::from_index(index, self.size)),
+                _ => {}
+            }
+
+            solution.probabilities.push(match cell {
+                _ if open => None,
+                Some(false) => Some(0.0),
+                Some(true) => Some(1.0),
+                None => Some(probabilities[index]),
+            });
+        }
+
+        solution
+    }
+
+    fn constraints(&self, cells: &[CellState], known: &[Option<bool>]) -> Vec<Constraint> {
+        let mut constraints = Vec::new();
+
+        for (index, cell) in cells.iter().enumerate() {
+            let CellState::Open(num) = *cell else {
+                continue;
+            };
+
+            let mut unknown = Vec::new();
+            let mut mines = 0;
+            for neighbor in Position::from_index(index, self.size).neighbors(self.size) {
+                let neighbor = neighbor.index(self.size);
+                match known[neighbor] {
+                    Some(true) => mines += 1,
+                    Some(false) => {}
+                    None => unknown.push(neighbor),
+                }
+            }
+
+            if !unknown.is_empty() {
+                unknown.sort_unstable();
+                constraints.push(Constraint {
+                    cells: unknown,
+                    mines: num.saturating_sub(mines),
+                });
+            }
+        }
+
+        constraints
+    }
+
+    fn apply_simple_rules(constraints: &[Constraint], known: &mut [Option<bool>]) -> bool {
+        let mut progress = false;
+        let mut mark = |cells: &mut dyn Iterator<Item = usize>, mine: bool| {
+            for cell in cells {
+                if known[cell].is_none() {
+                    known[cell] = Some(mine);
+                    progress = true;
+                }
+            }
+        };
+
+        for constraint in constraints {
+            if constraint.mines == 0 {
+                mark(&mut constraint.cells.iter().copied(), false);
+            } else if constraint.mines == constraint.cells.len() {
+                mark(&mut constraint.cells.iter().copied(), true);
+            }
+        }
+
+        let mut containing: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (index, constraint) in constraints.iter().enumerate() {
+            for &cell in &constraint.cells {
+                containing.entry(cell).or_default().push(index);
+            }
+        }
+
+        for (index, subset) in constraints.iter().enumerate() {
+            for &other in &containing[&subset.cells[0]] {
+                let superset = &constraints[other];
+                if other == index
+                    || superset.cells.len() <= subset.cells.len()
+                    || superset.mines < subset.mines
+                    || !subset
+                        .cells
+                        .iter()
+                        .all(|cell| superset.cells.binary_search(cell).is_ok())
+                {
+                    continue;
+                }
+
+                let mut difference = superset
+                    .cells
+                    .iter()
+                    .copied()
+                    .filter(|cell| subset.cells.binary_search(cell).is_err());
+                let mines = superset.mines - subset.mines;
+
+                if mines == 0 {
+                    mark(&mut difference, false);
+                } else if mines == superset.cells.len() - subset.cells.len() {
+                    mark(&mut difference, true);
+                }
+            }
+        }
+
+        progress
+    }
+
+    fn apply_enumeration(
+        &self,
+        constraints: &[Constraint],
+        known: &mut [Option<bool>],
+        probabilities: &mut [f64],
+    ) {
+        let mut components = Vec::new();
+        for constraint_indices in Self::group_constraints(constraints) {
+            match Self::enumerate_component(constraints, &constraint_indices) {
+                Some(component) => components.push(component),
+                None => return,
+            }
+        }
+
+        let frontier: usize = components.iter().map(|c| c.cells.len()).sum();
+        let rest = known.iter().filter(|cell| cell.is_none()).count() - frontier;
+        let known_mines = known.iter().filter(|&&cell| cell == Some(true)).count();
+        if known_mines > self.mine_amount {
+            return;
+        }
+        let mines_left = self.mine_amount - known_mines;
+
+        let ln_factorials = (0..=rest).fold(vec![0.0], |mut acc: Vec<f64>, n| {
+            if n > 0 {
+                acc.push(acc[n - 1] + (n as f64).ln());
+            }
+            acc
+        });
+        let ln_binomial =
+            |n: usize, k: usize| ln_factorials[n] - ln_factorials[k] - ln_factorials[n - k];
+        let rest_weights: Vec<f64> = {
+            let ln_weights: Vec<Option<f64>> = (0..=mines_left)
+                .map(|frontier_mines| {
+                    let rest_mines = mines_left - frontier_mines;
+                    (rest_mines <= rest).then(|| ln_binomial(rest, rest_mines))
+                })
+                .collect();
+            let max = ln_weights
+                .iter()
+                .flatten()
+                .copied()
+                .fold(f64::NEG_INFINITY, f64::max);
+            ln_weights
+                .iter()
+                .map(|weight| weight.map_or(0.0, |weight| (weight - max).exp()))
+                .collect()
+        };
+
+        let all = components.iter().fold(vec![1.0], |acc, component| {
+            convolve(&acc, &component.solutions)
+        });
+        let total: f64 = all
+            .iter()
+            .enumerate()
+            .map(|(mines, &count)| count * rest_weights.get(mines).copied().unwrap_or(0.0))
+            .sum();
+        if total == 0.0 {
+            return;
+        }
+
+        for (index, component) in components.iter().enumerate() {
+            let others = components
+                .iter()
+                .enumerate()
+                .filter(|&(other, _)| other != index)
+                .fold(vec![1.0], |acc, (_, other)| {
+                    convolve(&acc, &other.solutions)
+                });
+
+            for (cell, mine_solutions) in component.cells.iter().zip(&component.mine_solutions) {
+                let mut mine_weight = 0.0;
+                let mut safe_weight = 0.0;
+
+                for (component_mines, &solutions) in component.solutions.iter().enumerate() {
+                    for (other_mines, &other_solutions) in others.iter().enumerate() {
+                        let weight = other_solutions
+                            * rest_weights
+                                .get(component_mines + other_mines)
+                                .copied()
+                                .unwrap_or(0.0);
+                        mine_weight += mine_solutions[component_mines] * weight;
+                        safe_weight += (solutions - mine_solutions[component_mines]) * weight;
+                    }
+                }
+
+                if mine_weight == 0.0 {
+                    known[*cell] = Some(false);
+                } else if safe_weight == 0.0 {
+                    known[*cell] = Some(true);
+                }
+                probabilities[*cell] = mine_weight / (mine_weight + safe_weight);
+            }
+        }
+
+        if rest > 0 {
+            let rest_probability = all
+                .iter()
+                .enumerate()
+                .map(|(mines, &count)| {
+                    count
+                        * rest_weights.get(mines).copied().unwrap_or(0.0)
+                        * (mines_left.saturating_sub(mines)) as f64
+                        / rest as f64
+                })
+                .sum::<f64>()
+                / total;
+            let rest_certain = all
+                .iter()
+                .enumerate()
+                .filter(|&(mines, &count)| {
+                    count * rest_weights.get(mines).copied().unwrap_or(0.0) > 0.0
+                })
+                .map(|(mines, _)| mines_left - mines)
+                .try_fold(None, |certain, rest_mines| match (certain, rest_mines) {
+                    (None | Some(false), 0) => Some(Some(false)),
+                    (None | Some(true), rest_mines) if rest_mines == rest => Some(Some(true)),
+                    _ => None,
+                })
+                .flatten();
+
+            let mut in_frontier = vec![false; known.len()];
+            for component in &components {
+                for &cell in &component.cells {
+                    in_frontier[cell] = true;
+                }
+            }
+
+            for (index, cell) in known.iter_mut().enumerate() {
+                if cell.is_some() || in_frontier[index] {
+                    continue;
+                }
+
+                *cell = rest_certain;
+                probabilities[index] = rest_probability;
+            }
+        }
+    }
+
+    fn group_constraints(constraints: &[Constraint]) -> Vec<Vec<usize>> {
+        let mut parents: Vec<usize> = (0..constraints.len()).collect();
+        fn find(parents: &mut [usize], index: usize) -> usize {
+            if parents[index] != index {
+                parents[index] = find(parents, parents[index]);
+            }
+            parents[index]
+        }
+
+        let mut owners: HashMap<usize, usize> = HashMap::new();
+        for (index, constraint) in constraints.iter().enumerate() {
+            for &cell in &constraint.cells {
+                if let Some(&owner) = owners.get(&cell) {
+                    let (a, b) = (find(&mut parents, owner), find(&mut parents, index));
+                    parents[a] = b;
+                } else {
+                    owners.insert(cell, index);
+                }
+            }
+        }
+
+        let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+        for index in 0..constraints.len() {
+            groups
+                .entry(find(&mut parents, index))
+                .or_default()
+                .push(index);
+        }
+
+        groups.into_values().collect()
+    }
+
+    fn enumerate_component(
+        constraints: &[Constraint],
+        constraint_indices: &[usize],
+    ) -> Option<Component> {
+        let mut cells: Vec<usize> = constraint_indices
+            .iter()
+            .flat_map(|&index| constraints[index].cells.iter().copied())
+            .collect();
+        cells.sort_unstable();
+        cells.dedup();
+
+        let cell_constraints: Vec<Vec<usize>> = cells
+            .iter()
+            .map(|cell| {
+                constraint_indices
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &index)| constraints[index].cells.binary_search(cell).is_ok())
+                    .map(|(local, _)| local)
+                    .collect()
+            })
+            .collect();
+
+        let mut enumeration = Enumeration {
+            targets: constraint_indices
+                .iter()
+                .map(|&index| constraints[index].mines)
+                .collect(),
+            assigned: vec![0; constraint_indices.len()],
+            unassigned: constraint_indices
+                .iter()
+                .map(|&index| constraints[index].cells.len())
+                .collect(),
+            cell_constraints,
+            values: vec![false; cells.len()],
+            solutions: vec![0.0; cells.len() + 1],
+            mine_solutions: vec![vec![0.0; cells.len() + 1]; cells.len()],
+            steps: 0,
+        };
+
+        if !enumeration.run(0, 0) {
+            return None;
+        }
+
+        Some(Component {
+            cells,
+            solutions: enumeration.solutions,
+            mine_solutions: enumeration.mine_solutions,
+        })
+    }
+}
+
+struct Enumeration {
+    targets: Vec<usize>,
+    assigned: Vec<usize>,
+    unassigned: Vec<usize>,
+    cell_constraints: Vec<Vec<usize>>,
+    values: Vec<bool>,
+    solutions: Vec<f64>,
+    mine_solutions: Vec<Vec<f64>>,
+    steps: usize,
+}
+
+impl Enumeration {
+    fn run(&mut self, cell: usize, mines: usize) -> bool {
+        self.steps += 1;
+        if self.steps > MAX_ENUMERATION_STEPS {
+            return false;
+        }
+
+        if cell == self.values.len() {
+            self.solutions[mines] += 1.0;
+            for (index, &value) in self.values.iter().enumerate() {
+                if value {
+                    self.mine_solutions[index][mines] += 1.0;
+                }
+            }
+            return true;
+        }
+
+        for value in [false, true] {
+            let feasible = self.cell_constraints[cell].iter().all(|&constraint| {
+                let assigned = self.assigned[constraint] + value as usize;
+                assigned <= self.targets[constraint]
+                    && assigned + self.unassigned[constraint] > self.targets[constraint]
+            });
+            if !feasible {
+                continue;
+            }
+
+            for &constraint in &self.cell_constraints[cell] {
+                self.assigned[constraint] += value as usize;
+                self.unassigned[constraint] -= 1;
+            }
+            self.values[cell] = value;
+
+            let finished = self.run(cell + 1, mines + value as usize);
+
+            for &constraint in &self.cell_constraints[cell] {
+                self.assigned[constraint] -= value as usize;
+                self.unassigned[constraint] += 1;
+            }
+            self.values[cell] = false;
+
+            if !finished {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut result = vec![0.0; a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            result[i + j] += x * y;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One string per row: '#' is a closed cell, 'F' a flag and a digit an open number.
+    fn board(rows: &[&str]) -> ((usize, usize), Vec<CellState>) {
+        let size = (rows[0].len(), rows.len());
+        let cells = rows
+            .iter()
+            .flat_map(|row| row.chars())
+            .map(|cell| match cell {
+                '#' => CellState::Closed,
+                'F' => CellState::Flagged,
+                num => CellState::Open(num.to_digit(10).unwrap() as usize),
+            })
+            .collect();
+
+        (size, cells)
+    }
+
+    fn coordinates(positions: &[Position]) -> Vec<(usize, usize)> {
+        let mut coordinates: Vec<_> = positions.iter().map(|pos| (pos.x, pos.y)).collect();
+        coordinates.sort_unstable();
+        coordinates
+    }
+
+    #[test]
+    fn one_two_one() {
+        let (size, cells) = board(&["#####", "11211", "00000"]);
+
+        // The subset rules alone are enough, without enumerating anything.
+        let solution = Solver::new(size, 2).solve_lazily(&cells);
+
+        assert_eq!(coordinates(&solution.mines), [(1, 0), (3, 0)]);
+        assert_eq!(coordinates(&solution.safe), [(0, 0), (2, 0), (4, 0)]);
+    }
+
+    #[test]
+    fn forced_fifty_fifty() {
+        let (size, cells) = board(&["##", "11", "00"]);
+
+        let solution = Solver::new(size, 1).solve(&cells);
+
+        assert!(solution.safe.is_empty());
+        assert!(solution.mines.is_empty());
+        assert_eq!(solution.probability(&Position { x: 0, y: 0 }), Some(0.5));
+        assert_eq!(solution.probability(&Position { x: 1, y: 0 }), Some(0.5));
+        assert!(solution.hint().is_some_and(|hint| hint.y == 0));
+    }
+
+    #[test]
+    fn probabilities_add_up_to_the_mines_left() {
+        let (size, cells) = board(&["1####", "#####", "##F##", "#####", "####2"]);
+
+        let solution = Solver::new(size, 6).solve(&cells);
+
+        let total: f64 = solution.probabilities.iter().flatten().sum();
+        assert!((total - 6.0).abs() < 1e-9, "{}", total);
+
+        let hint = solution.hint().unwrap();
+        let lowest = solution
+            .probabilities
+            .iter()
+            .zip(&solution.flagged)
+            .filter(|(_, &flagged)| !flagged)
+            .filter_map(|(probability, _)| *probability)
+            .fold(f64::INFINITY, f64::min);
+        assert_eq!(solution.probability(&hint), Some(lowest));
+        assert!(hint != Position { x: 2, y: 2 });
+    }
+
+    #[test]
+    fn gives_up_on_huge_components() {
+        // Every other cell of every other row is an open 2, which ties all closed cells into one
+        // component with far too many solutions.
+        let rows: Vec<String> = (0..15)
+            .map(|y| {
+                (0..15)
+                    .map(|x| if x % 2 == 1 && y % 2 == 1 { '2' } else { '#' })
+                    .collect()
+            })
+            .collect();
+        let (size, cells) = board(&rows.iter().map(String::as_str).collect::<Vec<_>>());
+        let closed = cells
+            .iter()
+            .filter(|&&cell| cell == CellState::Closed)
+            .count();
+        let mine_amount = 60;
+
+        let solution = Solver::new(size, mine_amount).solve(&cells);
+
+        // Falls back to the plain mine density instead of enumerating forever.
+        let density = mine_amount as f64 / closed as f64;
+        assert!(solution.safe.is_empty());
+        assert!(solution.mines.is_empty());
+        assert!(solution
+            .probabilities
+            .iter()
+            .flatten()
+            .all(|&probability| probability == density));
+    }
+}
@@ -0,0 +1,275 @@
+use std::time::Duration;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    field::Field, field_generator::set_numbers, fields::Fields, position::Position,
+    replay_file::ReplayFile, tile::Tile, won::Won,
+};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+pub struct ClickStats {
+    pub total: usize,
+    pub effective: usize,
+}
+
+impl ClickStats {
+    pub fn wasted(&self) -> usize {
+        self.total - self.effective
+    }
+
+    fn count(&mut self, effective: bool) {
+        self.total += 1;
+        if effective {
+            self.effective += 1;
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ClickKind {
+    Left,
+    Right,
+    Chord,
+    Question,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct Stats {
+    pub bbbv: usize,
+    pub solved_bbbv: usize,
+    pub duration: Duration,
+    pub won: bool,
+
+    pub left: ClickStats,
+    pub right: ClickStats,
+    pub chord: ClickStats,
+    // Right clicks that turned a flag into a question mark. They're not clicks in the usual sense,
+    // so they're left out of the totals and don't lower IOE or correctness.
+    #[serde(default)]
+    pub questions: usize,
+}
+
+impl Stats {
+    pub fn from_replay(replay_file: &ReplayFile) -> Self {
+        let size = replay_file.size;
+
+        let mut stats = Self {
+            bbbv: 0,
+            solved_bbbv: 0,
+            duration: replay_file.duration,
+            won: false,
+
+            left: ClickStats::default(),
+            right: ClickStats::default(),
+            chord: ClickStats::default(),
+            questions: 0,
+        };
+
+        let fields = Self::simulate(replay_file, |kind, effective| match kind {
+            ClickKind::Left => stats.left.count(effective),
+            ClickKind::Right => stats.right.count(effective),
+            ClickKind::Chord => stats.chord.count(effective),
+            ClickKind::Question => stats.questions += 1,
+        });
+
+        let mut board = vec![Field::new(); size.0 * size.1];
+        for mine in &replay_file.mines {
+            board[mine.index(size)].set_mine();
+        }
+        set_numbers(&mut board, size);
+
+        let opened = |index: usize| {
+            fields
+                .as_ref()
+                .is_some_and(|fields| fields.get_fields()[index].is_open())
+        };
+
+        let mut counted = vec![false; board.len()];
+        for index in 0..board.len() {
+            if counted[index] || board[index].get_tile() != Tile::Empty {
+                continue;
+            }
+
+            stats.bbbv += 1;
+            let mut solved = false;
+            let mut stack = vec![index];
+            while let Some(index) = stack.pop() {
+                if counted[index] {
+                    continue;
+                }
+                counted[index] = true;
+
+                if board[index].get_tile() == Tile::Empty {
+                    solved |= opened(index);
+                    stack.extend(
+                        Position::from_index(index, size)
+                            .neighbors(size)
+                            .iter()
+                            .map(|pos| pos.index(size)),
+                    );
+                }
+            }
+
+            if solved {
+                stats.solved_bbbv += 1;
+            }
+        }
+
+        for index in 0..board.len() {
+            if !counted[index] && !board[index].is_mine() {
+                stats.bbbv += 1;
+                if opened(index) {
+                    stats.solved_bbbv += 1;
+                }
+            }
+        }
+
+        stats.won = matches!(fields.map(|fields| fields.won), Some(Won::Won));
+
+        stats
+    }
+
+    pub fn click_kinds(replay_file: &ReplayFile) -> Vec<ClickKind> {
+        let mut kinds = Vec::with_capacity(replay_file.clicks.len());
+        Self::simulate(replay_file, |kind, _| kinds.push(kind));
+
+        kinds
+    }
+
+    // Plays the clicks until the game ends, reporting every click's kind and whether it did something.
+    fn simulate(
+        replay_file: &ReplayFile,
+        mut on_click: impl FnMut(ClickKind, bool),
+    ) -> Option<Fields> {
+        let mut fields: Option<Fields> = None;
+        for click in &replay_file.clicks {
+            if fields
+                .as_ref()
+                .is_some_and(|fields| !fields.won.still_playing())
+            {
+                break;
+            }
+
+            let (kind, effective) = replay_file.apply_click(&mut fields, click);
+            on_click(kind, effective);
+        }
+
+        fields
+    }
+
+    pub fn clicks(&self) -> usize {
+        self.left.total + self.right.total + self.chord.total
+    }
+
+    pub fn effective_clicks(&self) -> usize {
+        self.left.effective + self.right.effective + self.chord.effective
+    }
+
+    pub fn wasted_clicks(&self) -> usize {
+        self.clicks() - self.effective_clicks()
+    }
+
+    pub fn bbbv_per_second(&self) -> f64 {
+        if self.duration.is_zero() {
+            0.0
+        } else {
+            self.solved_bbbv as f64 / self.duration.as_secs_f64()
+        }
+    }
+
+    pub fn ioe(&self) -> f64 {
+        ratio(self.solved_bbbv, self.clicks())
+    }
+
+    pub fn throughput(&self) -> f64 {
+        ratio(self.solved_bbbv, self.effective_clicks())
+    }
+
+    pub fn correctness(&self) -> f64 {
+        ratio(self.effective_clicks(), self.clicks())
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::click::{ChordButtons, Click};
+
+    // . 2 * 2 .
+    // . 2 * 2 .
+    // . 1 2 1 .
+    //
+    // Two openings plus the 2 in the middle of the bottom row make a 3BV of 3.
+    fn replay_file(clicks: Vec<Click>) -> ReplayFile {
+        ReplayFile::new(
+            vec![Position { x: 2, y: 0 }, Position { x: 2, y: 1 }],
+            (5, 3),
+            clicks,
+            Duration::from_secs(10),
+            None,
+            Vec::new(),
+        )
+    }
+
+    fn pos(x: usize, y: usize) -> Position {
+        Position { x, y }
+    }
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn counts_clicks() {
+        let stats = Stats::from_replay(&replay_file(vec![
+            Click::Reveal(pos(0, 0), secs(0)),
+            Click::Flag(pos(2, 0), secs(1)),
+            // Taking a flag back off doesn't do anything useful.
+            Click::Flag(pos(2, 0), secs(2)),
+            Click::Flag(pos(2, 1), secs(3)),
+            Click::Question(pos(2, 1), secs(4)),
+            // Already open.
+            Click::Reveal(pos(0, 1), secs(5)),
+            // Neither mine next to it is flagged any more.
+            Click::Chord(pos(1, 1), secs(6), ChordButtons::Middle),
+            Click::Flag(pos(2, 0), secs(7)),
+            Click::Reveal(pos(2, 2), secs(8)),
+            Click::Reveal(pos(4, 0), secs(9)),
+        ]));
+
+        assert!(stats.won);
+        assert_eq!((stats.bbbv, stats.solved_bbbv), (3, 3));
+        assert_eq!((stats.left.total, stats.left.effective), (4, 3));
+        assert_eq!((stats.right.total, stats.right.effective), (4, 3));
+        assert_eq!((stats.chord.total, stats.chord.effective), (1, 0));
+        assert_eq!(stats.questions, 1);
+
+        assert_eq!((stats.clicks(), stats.effective_clicks()), (9, 6));
+        assert_eq!(stats.bbbv_per_second(), 0.3);
+        assert_eq!(stats.ioe(), 3.0 / 9.0);
+        assert_eq!(stats.throughput(), 3.0 / 6.0);
+        assert_eq!(stats.correctness(), 6.0 / 9.0);
+    }
+
+    #[test]
+    fn stops_at_the_end_of_the_game() {
+        let stats = Stats::from_replay(&replay_file(vec![
+            Click::Reveal(pos(0, 0), secs(0)),
+            Click::Reveal(pos(2, 1), secs(1)),
+            Click::Reveal(pos(4, 0), secs(2)),
+        ]));
+
+        assert!(!stats.won);
+        assert_eq!((stats.bbbv, stats.solved_bbbv), (3, 1));
+        assert_eq!((stats.left.total, stats.left.effective), (2, 2));
+    }
+}
@@ -0,0 +1,10 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::number::Number;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    Empty,
+    Mine,
+    Number(Number),
+}
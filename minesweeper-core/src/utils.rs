@@ -0,0 +1,10 @@
+use std::{collections::HashSet, hash::Hash};
+
+pub(crate) fn has_unique_elements<T>(iter: T) -> bool
+where
+    T: IntoIterator,
+    T::Item: Eq + Hash,
+{
+    let mut uniq = HashSet::new();
+    iter.into_iter().all(move |x| uniq.insert(x))
+}
@@ -0,0 +1,9 @@
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    Closed,
+    Open,
+    Flagged,
+    Questioned,
+}
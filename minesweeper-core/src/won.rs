@@ -0,0 +1,24 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::position::Position;
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub enum Won {
+    None,
+    Won,
+    Lost(Position),
+}
+
+impl Won {
+    pub fn still_playing(&self) -> bool {
+        matches!(self, Self::None)
+    }
+
+    pub fn win(&mut self) {
+        *self = Self::Won;
+    }
+
+    pub fn lose(&mut self, position: Position) {
+        *self = Self::Lost(position);
+    }
+}
@@ -0,0 +1,90 @@
+# Writes the AVF and RMV fixtures the import tests load. They're built by hand from the file
+# layouts the importer follows, so run this again after changing it:
+#
+#     python3 minesweeper-core/test-data/generate.py
+
+import os
+import struct
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+
+def center(cell):
+    return cell * 16 + 8
+
+# ---------- AVF: won beginner game ----------
+# mines: a wall at x=3 plus (0,0) and (7,7)
+avf_mines = [(3, y) for y in range(8)] + [(0, 0), (7, 7)]
+MOVE, LDOWN, LUP, RDOWN, RUP, MDOWN, MUP = 1, 3, 5, 9, 17, 33, 65
+
+def avf_event(kind, cx, cy, t_ms, dx=0, dy=0):
+    x, y = center(cx) + dx, center(cy) + dy
+    sec = t_ms // 1000 + 1
+    hundredths = (t_ms % 1000) // 10
+    return bytes([kind, x >> 8, sec & 0xff, x & 0xff, hundredths, y >> 8, sec >> 8, y & 0xff])
+
+events = [
+    (MOVE, 1, 1, 100, -5, -5),
+    (LDOWN, 1, 1, 200), (LUP, 1, 1, 250),      # reveal (1,1), a 1
+    (MOVE, 0, 0, 400),
+    (RDOWN, 0, 0, 500), (RUP, 0, 0, 560),      # flag (0,0)
+    (MOVE, 1, 1, 700),
+    (MDOWN, 1, 1, 800), (MUP, 1, 1, 850),      # middle chord (1,1), opens the left side
+    (MOVE, 2, 3, 1100),
+    (LDOWN, 2, 3, 1200), (RDOWN, 2, 3, 1230),  # L+R chord on (2,3), nothing to open
+    (RUP, 2, 3, 1300), (LUP, 2, 3, 1320),
+    (MOVE, 6, 0, 1600, 3, 2),
+    (LDOWN, 6, 0, 1700), (LUP, 6, 0, 1790),    # reveal (6,0), opens the rest and wins
+]
+avf = bytearray()
+avf += bytes([2, 0, 0, 0, 0, 3])  # version, 4 unknown bytes, beginner
+for x, y in avf_mines:
+    avf += bytes([y + 1, x + 1])
+# Free-form info section, with a few bytes that almost look like an event.
+avf += b"\x17\x05\x011\x02\x00\x00" + b"[0|7.11.2023|1700000000|B10T2]" + b"Tester\x00" + b"\x03\x00\x02\x00"
+for event in events:
+    avf += avf_event(*event)
+avf += bytes(8)  # seconds of zero end the events
+avf += b"cs=6D2F1A\x00Skin: Winmine XP\x00"
+open(os.path.join(HERE, "beginner_won.avf"), "wb").write(avf)
+
+# ---------- RMV: lost beginner game ----------
+# mines: a wall at x=4 plus (7,0) and (6,7)
+rmv_mines = [(4, y) for y in range(8)] + [(7, 0), (6, 7)]
+R_MOVE, R_LDOWN, R_LUP, R_RDOWN, R_RUP = 1, 2, 3, 4, 5
+
+def rmv_event(kind, cx, cy, t_ms):
+    return bytes([kind]) + t_ms.to_bytes(3, "big") + struct.pack(">HH", center(cx) + 12, center(cy) + 56)
+
+rmv_events = bytearray()
+for event in [
+    (R_MOVE, 0, 0, 50),
+    (R_LDOWN, 0, 0, 100), (R_LUP, 0, 0, 180),     # reveal (0,0), opens the left side
+]:
+    rmv_events += rmv_event(*event)
+rmv_events += bytes([9, 0, 0])                    # a board event, skipped
+for event in [
+    (R_MOVE, 4, 0, 300),
+    (R_RDOWN, 4, 0, 400), (R_RUP, 4, 0, 450),     # flag (4,0)
+    (R_MOVE, 3, 0, 600),
+    (R_LDOWN, 3, 0, 700), (R_RDOWN, 3, 0, 720),   # L+R chord on (3,0), released left first
+    (R_LUP, 3, 0, 800), (R_RUP, 3, 0, 820),
+    (R_MOVE, 7, 0, 1000),
+    (R_LDOWN, 7, 0, 1100), (R_LUP, 7, 0, 1150),   # reveal (7,0), a mine
+]:
+    rmv_events += rmv_event(*event)
+rmv_events += bytes([16])                         # game lost
+
+result = b"Lost"
+version_info = b"Viennasweeper 3.1"
+player_info = b"\x00\x06Tester"
+board = bytes([0, 0, 0, 0, 8, 8]) + struct.pack(">H", len(rmv_mines))
+for x, y in rmv_mines:
+    board += bytes([x, y])
+properties = bytes([0, 0, 0, 0, 1])
+checksum = bytes(range(16))
+
+rmv = bytearray(b"*rmv")
+rmv += struct.pack(">HHHHHHH", 1, len(result), len(version_info), len(player_info), len(board), 0, len(properties))
+rmv += struct.pack(">I", len(rmv_events)) + struct.pack(">H", len(checksum))
+rmv += result + version_info + player_info + board + properties + rmv_events + checksum
+open(os.path.join(HERE, "beginner_lost.rmv"), "wb").write(rmv)
@@ -1,24 +1,25 @@
 pub(crate) mod play_board;
 pub(crate) mod replay_board;
 
-use std::marker::PhantomData;
+use std::time::Duration;
 
 use eframe::{
-    egui,
+    egui::{self, Image},
     epaint::{Color32, Rect, Rounding},
 };
+use minesweeper_core::{
+    field_generator::FieldGenerator, fields::Fields, position::Position, won::Won,
+};
 
 use crate::{
     border::Border,
-    field_generator::FieldGenerator,
-    fields::Fields,
-    get_mine_amount, get_size, get_sound_player,
-    position::Position,
+    fields::DrawFields,
+    get_sound_player,
+    position::ScreenPosition,
     seven_segment_number::SevenSegmentNumber,
     sounds::{SoundPlayer, Sounds},
     textures::Textures,
     time::Time,
-    won::Won,
     BORDER_HEIGHT, BORDER_WIDTH, FIELD_SIZE,
 };
 
@@ -27,9 +28,13 @@ where
     TheFieldGenerator: FieldGenerator,
 {
     pub(crate) time: Time,
+    pub(crate) max_duration: Option<Duration>,
+
+    pub(crate) fields: Option<Fields>,
+    pub(crate) field_generator: TheFieldGenerator,
 
-    pub(crate) fields: Option<Fields<TheFieldGenerator>>,
-    the_field_generator_phantom: PhantomData<TheFieldGenerator>,
+    pub(crate) size: (usize, usize),
+    pub(crate) mine_amount: usize,
 
     border: Border,
     textures: Textures,
@@ -40,12 +45,20 @@ impl<TheFieldGenerator> Board<TheFieldGenerator>
 where
     TheFieldGenerator: FieldGenerator,
 {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(
+        field_generator: TheFieldGenerator,
+        size: (usize, usize),
+        mine_amount: usize,
+    ) -> Self {
         Self {
-            time: Time::None,
+            time: Time::new(),
+            max_duration: None,
 
             fields: None,
-            the_field_generator_phantom: PhantomData,
+            field_generator,
+
+            size,
+            mine_amount,
 
             border: Border::new(),
             textures: Textures::new(),
@@ -58,6 +71,25 @@ where
         self.fields = None;
     }
 
+    pub(crate) fn change_settings(
+        &mut self,
+        field_generator: TheFieldGenerator,
+        size: (usize, usize),
+        mine_amount: usize,
+    ) {
+        self.field_generator = field_generator;
+        self.size = size;
+        self.mine_amount = mine_amount;
+        self.reset();
+    }
+
+    pub(crate) fn duration(&self) -> Duration {
+        match self.max_duration {
+            Some(max_duration) => self.time.duration().min(max_duration),
+            None => self.time.duration(),
+        }
+    }
+
     pub(crate) fn still_playing(&self) -> bool {
         if let Some(fields) = &self.fields {
             fields.won.still_playing()
@@ -77,7 +109,7 @@ where
             .fields
             .get_or_insert_with(|| {
                 self.time.start();
-                Fields::<TheFieldGenerator>::new(position)
+                Fields::new(&self.field_generator, self.size, self.mine_amount, position)
             })
             .reveal(position, true)
         {
@@ -90,6 +122,26 @@ where
             }
         }
 
+        self.check_won(play_sound);
+    }
+
+    pub(crate) fn chord(&mut self, position: &Position, play_sound: bool) {
+        let Some(fields) = self
+            .fields
+            .as_mut()
+            .filter(|fields| fields.won.still_playing())
+        else {
+            return;
+        };
+
+        if fields.chord(position) && play_sound {
+            self.sound_player.play(Sounds::OpenFromFlags);
+        }
+
+        self.check_won(play_sound);
+    }
+
+    fn check_won(&mut self, play_sound: bool) {
         self.fields.as_mut().unwrap().check_won();
         match self.fields.as_ref().unwrap().won {
             Won::None => {}
@@ -126,6 +178,18 @@ where
         }
     }
 
+    pub(crate) fn question(&mut self, position: &Position, play_sound: bool) {
+        if let Some(fields) = &mut self.fields {
+            if !fields.won.still_playing() {
+                return;
+            }
+
+            if fields.question(position) && play_sound {
+                self.sound_player.play(Sounds::Question);
+            }
+        }
+    }
+
     pub(crate) fn draw(&self, ui: &mut egui::Ui) {
         ui.painter().rect_filled(
             Rect::EVERYTHING,
@@ -141,28 +205,48 @@ where
 
         match &self.fields {
             Some(fields) => fields.draw(field_textures, ui),
-            None => Fields::<TheFieldGenerator>::draw_empty(field_textures, ui),
+            None => Fields::draw_empty(self.size, field_textures, ui),
         }
-        self.border.draw(ui);
-
-        self.fields
-            .as_ref()
-            .map(|fields| fields.mines)
-            .unwrap_or_else(|| SevenSegmentNumber::new(*get_mine_amount() as i32))
-            .draw(
-                BORDER_WIDTH - 1.0,
-                BORDER_HEIGHT,
-                number_bg_texture,
-                number_textures,
-                ui,
-            );
+        self.border.draw(self.size, ui);
+
+        SevenSegmentNumber::new(
+            self.fields
+                .as_ref()
+                .map(|fields| fields.mines_left)
+                .unwrap_or(self.mine_amount as i32),
+        )
+        .draw(
+            BORDER_WIDTH - 1.0,
+            BORDER_HEIGHT,
+            number_bg_texture,
+            number_textures,
+            ui,
+        );
 
-        SevenSegmentNumber::new(self.time.duration().as_secs() as i32).draw(
-            BORDER_WIDTH + FIELD_SIZE * get_size().0 as f32 - number_bg_texture.width() as f32,
+        SevenSegmentNumber::new(self.duration().as_secs() as i32).draw(
+            BORDER_WIDTH + FIELD_SIZE * self.size.0 as f32 - number_bg_texture.width() as f32,
             BORDER_HEIGHT,
             number_bg_texture,
             number_textures,
             ui,
         );
     }
+
+    // Draws the cells under a held button sunk in, like an open empty cell.
+    pub(crate) fn draw_pressed(&self, positions: &[Position], ui: &mut egui::Ui) {
+        let texture = &self.textures.field_textures[1];
+        for position in positions {
+            Image::new(
+                texture.texture_id(ui.ctx()),
+                egui::vec2(FIELD_SIZE, FIELD_SIZE),
+            )
+            .paint_at(
+                ui,
+                Rect::from_center_size(
+                    position.coordinates().into(),
+                    egui::vec2(FIELD_SIZE, FIELD_SIZE),
+                ),
+            );
+        }
+    }
 }
@@ -1,51 +1,562 @@
-use std::{fs, io};
+use std::{path::Path, time::Duration};
 
-use eframe::egui::{self, Key, PointerButton};
+use eframe::{
+    egui::{self, Event, Key, PointerButton, Pos2},
+    emath::Align2,
+    epaint::{Color32, FontId, Rect, Rounding, Stroke},
+};
+use minesweeper_core::{
+    click::{ChordButtons, Click},
+    field_generator::{
+        no_guess_field_generator::NoGuessFieldGenerator, play_field_generator::PlayFieldGenerator,
+        FieldGenerator,
+    },
+    fields::Fields,
+    mouse_event::{MouseAction, MouseButton, MouseButtons, MouseEvent},
+    position::Position,
+    replay_file::{
+        metadata::{Generator, Metadata},
+        ReplayFile, ReplayFormat,
+    },
+    saved_game::{SavedGame, SAVED_GAME_VERSION},
+    solver::{Solution, Solver},
+    stats::Stats,
+    tile::Tile,
+};
 
 use crate::{
-    board::Board, click::Click, field_generator::play_field_generator::PlayFieldGenerator,
-    get_mine_amount, get_size, position::Position, replay_file::ReplayFile,
+    board::Board, mouse_event::ScreenMouseEvent, player, position::ScreenPosition, signing,
+    stats::draw_stats_window, utils::replay_file_name, FIELD_SIZE,
 };
 
+// What a click on the board does, worked out from the buttons and the chording settings.
+#[derive(Clone, Copy)]
+enum Action {
+    Reveal,
+    Flag,
+    Chord(ChordButtons),
+}
+
+// A press or release of one of the buttons replays know about.
+fn button_action(event: &Event) -> Option<(MouseAction, Pos2)> {
+    let Event::PointerButton {
+        pos,
+        button,
+        pressed,
+        ..
+    } = *event
+    else {
+        return None;
+    };
+
+    let button = match button {
+        PointerButton::Primary => MouseButton::Left,
+        PointerButton::Secondary => MouseButton::Right,
+        PointerButton::Middle => MouseButton::Middle,
+        _ => return None,
+    };
+    if pressed {
+        Some((MouseAction::Down(button), pos))
+    } else {
+        Some((MouseAction::Up(button), pos))
+    }
+}
+
 pub(crate) struct PlayBoard {
-    board: Board<PlayFieldGenerator>,
+    board: Board<Box<dyn FieldGenerator>>,
+    seed: u64,
+    seeded: bool,
+    no_guess: bool,
+    // Set when the no guessing generator gave up on a dense board and this one needs a guess.
+    needs_guess: bool,
+    player_name: String,
 
     clicks: Vec<Click>,
+    mouse_events: Vec<MouseEvent>,
+    started_at: Option<u64>,
+
+    // In practice mode every click saves the board from before it and where its mouse events
+    // start, so it can be taken back.
+    practice: bool,
+    assisted: bool,
+    undo_stack: Vec<(Option<Fields>, usize)>,
+    redo_stack: Vec<(Click, Vec<MouseEvent>)>,
+
+    // Right clicking a flag turns it into a question mark instead of clearing it.
+    question_marks: bool,
+    left_click_chords: bool,
+    right_click_flags_around: bool,
+    buttons: MouseButtons,
+
+    solution: Option<Solution>,
+    hint: Option<Position>,
+    show_probabilities: bool,
+
+    stats: Option<Stats>,
+    won_replay: Option<ReplayFile>,
+    rank: Option<usize>,
+    signing_key: Option<[u8; 32]>,
+    // What S saves the replay as.
+    replay_format: ReplayFormat,
 }
 
 impl PlayBoard {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(size: (usize, usize), mine_amount: usize, seed: Option<u64>) -> Self {
+        let seeded = seed.is_some();
+        let seed = seed.unwrap_or_else(rand::random);
+
         Self {
-            board: Board::new(),
+            board: Board::new(Self::field_generator(seed, false), size, mine_amount),
+            seed,
+            seeded,
+            no_guess: false,
+            needs_guess: false,
+            player_name: player::load_name(),
 
             clicks: Vec::new(),
+            mouse_events: Vec::new(),
+            started_at: None,
+
+            practice: false,
+            assisted: false,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+
+            question_marks: false,
+            left_click_chords: true,
+            right_click_flags_around: true,
+            buttons: MouseButtons::default(),
+
+            solution: None,
+            hint: None,
+            show_probabilities: false,
+
+            stats: None,
+            won_replay: None,
+            rank: None,
+            signing_key: signing::load_or_create_key()
+                .map_err(|err| println!("Couldn't load signing key: {}", err))
+                .ok(),
+            replay_format: ReplayFormat::Json,
         }
     }
 
+    fn field_generator(seed: u64, no_guess: bool) -> Box<dyn FieldGenerator> {
+        if no_guess {
+            Box::new(NoGuessFieldGenerator::new(seed))
+        } else {
+            Box::new(PlayFieldGenerator::new(seed))
+        }
+    }
+
+    // A seed the user picked sticks around for every new game until it's cleared.
     pub(crate) fn reset(&mut self) {
+        if self.seeded {
+            self.set_seed(self.seed);
+        } else {
+            self.clear_seed();
+        }
+    }
+
+    pub(crate) fn change_settings(&mut self, size: (usize, usize), mine_amount: usize) {
+        if !self.seeded {
+            self.seed = rand::random();
+        }
+        self.board.change_settings(
+            Self::field_generator(self.seed, self.no_guess),
+            size,
+            mine_amount,
+        );
+        self.clear_game();
+    }
+
+    pub(crate) fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.seeded = true;
+        self.board.field_generator = Self::field_generator(self.seed, self.no_guess);
         self.board.reset();
+        self.clear_game();
+    }
+
+    fn clear_game(&mut self) {
         self.clicks.clear();
+        self.mouse_events.clear();
+        self.started_at = None;
+        self.assisted = false;
+        self.needs_guess = false;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.solution = None;
+        self.hint = None;
+        self.stats = None;
+        self.won_replay = None;
+        self.rank = None;
+    }
+
+    pub(crate) fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub(crate) fn seeded(&self) -> bool {
+        self.seeded
+    }
+
+    pub(crate) fn clear_seed(&mut self) {
+        self.set_seed(rand::random());
+        self.seeded = false;
+    }
+
+    pub(crate) fn set_no_guess(&mut self, no_guess: bool) {
+        self.no_guess = no_guess;
+        self.reset();
+    }
+
+    pub(crate) fn no_guess(&self) -> bool {
+        self.no_guess
+    }
+
+    pub(crate) fn needs_guess(&self) -> bool {
+        self.needs_guess
+    }
+
+    fn check_needs_guess(&mut self) {
+        let first_reveal = self
+            .clicks
+            .iter()
+            .find(|click| matches!(click, Click::Reveal(..)));
+        self.needs_guess = match (&self.board.fields, first_reveal) {
+            (Some(fields), Some(first_reveal)) if self.no_guess => {
+                !NoGuessFieldGenerator::is_solvable(
+                    fields.get_fields(),
+                    self.board.size,
+                    self.board.mine_amount,
+                    &first_reveal.position(),
+                )
+            }
+            _ => false,
+        };
+    }
+
+    pub(crate) fn set_practice(&mut self, practice: bool) {
+        self.practice = practice;
+        if !practice {
+            self.undo_stack.clear();
+            self.redo_stack.clear();
+        }
+    }
+
+    pub(crate) fn practice(&self) -> bool {
+        self.practice
+    }
+
+    pub(crate) fn set_question_marks(&mut self, question_marks: bool) {
+        self.question_marks = question_marks;
+    }
+
+    pub(crate) fn question_marks(&self) -> bool {
+        self.question_marks
+    }
+
+    pub(crate) fn set_replay_format(&mut self, replay_format: ReplayFormat) {
+        self.replay_format = replay_format;
+    }
+
+    pub(crate) fn replay_format(&self) -> ReplayFormat {
+        self.replay_format
+    }
+
+    pub(crate) fn set_left_click_chords(&mut self, left_click_chords: bool) {
+        self.left_click_chords = left_click_chords;
+    }
+
+    pub(crate) fn left_click_chords(&self) -> bool {
+        self.left_click_chords
+    }
+
+    pub(crate) fn set_right_click_flags_around(&mut self, right_click_flags_around: bool) {
+        self.right_click_flags_around = right_click_flags_around;
+    }
+
+    pub(crate) fn right_click_flags_around(&self) -> bool {
+        self.right_click_flags_around
+    }
+
+    pub(crate) fn player_name(&self) -> &str {
+        &self.player_name
+    }
+
+    pub(crate) fn set_player_name(&mut self, player_name: String) {
+        if let Err(err) = player::save_name(&player_name) {
+            println!("Couldn't save player name: {}", err);
+        }
+        self.player_name = player_name;
+    }
+
+    pub(crate) fn size(&self) -> (usize, usize) {
+        self.board.size
+    }
+
+    fn generator(&self) -> Generator {
+        if self.no_guess {
+            Generator::NoGuess
+        } else if self.seeded {
+            Generator::Seeded
+        } else {
+            Generator::Random
+        }
     }
 
     pub(crate) fn create_replay(&self) -> Option<ReplayFile> {
         if self.board.still_playing() {
             None
         } else {
-            Some(ReplayFile::new(
-                self.board
-                    .fields
-                    .as_ref()
-                    .unwrap()
-                    .get_fields()
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, f)| f.is_mine())
-                    .map(|(i, _)| Position::from_index(i))
-                    .collect(),
+            let mut replay_file = ReplayFile::new(
+                self.board.fields.as_ref().unwrap().get_mines(),
+                self.board.size,
                 self.clicks.clone(),
-                self.board.time.duration(),
-            ))
+                self.board.duration(),
+                Some(self.seed()),
+                self.mouse_events.clone(),
+            );
+            replay_file.metadata = Some(Metadata::new(
+                self.player_name.clone(),
+                self.started_at.unwrap_or_default(),
+                env!("CARGO_PKG_VERSION").to_string(),
+                self.generator(),
+                self.assisted,
+                self.board.mine_amount,
+                &replay_file,
+            ));
+            if let Some(signing_key) = &self.signing_key {
+                replay_file.sign(signing_key);
+            }
+
+            Some(replay_file)
+        }
+    }
+
+    // The game so far, if one has been started and isn't over yet.
+    pub(crate) fn saved_game(&self) -> Option<SavedGame> {
+        let fields = self.board.fields.as_ref()?;
+        if !fields.won.still_playing() {
+            return None;
         }
+
+        Some(SavedGame {
+            version: SAVED_GAME_VERSION,
+            size: self.board.size,
+            mine_amount: self.board.mine_amount,
+            seed: self.seed,
+            seeded: self.seeded,
+            generator: self.generator(),
+
+            fields: fields.clone(),
+            clicks: self.clicks.clone(),
+            mouse_events: self.mouse_events.clone(),
+            elapsed: self.board.duration(),
+            started_at: self.started_at,
+            assisted: self.assisted,
+        })
+    }
+
+    // Picks a saved game back up, with the clock paused until the next click.
+    pub(crate) fn resume(&mut self, saved_game: SavedGame) {
+        self.seed = saved_game.seed;
+        self.seeded = saved_game.seeded;
+        self.no_guess = saved_game.generator == Generator::NoGuess;
+        self.board.change_settings(
+            Self::field_generator(self.seed, self.no_guess),
+            saved_game.size,
+            saved_game.mine_amount,
+        );
+        self.clear_game();
+
+        self.board.fields = Some(saved_game.fields);
+        self.board.time.seek(saved_game.elapsed);
+        self.clicks = saved_game.clicks;
+        self.mouse_events = saved_game.mouse_events;
+        self.started_at = saved_game.started_at;
+        self.assisted = saved_game.assisted;
+        self.check_needs_guess();
+    }
+
+    pub(crate) fn take_won_replay(&mut self) -> Option<ReplayFile> {
+        self.won_replay.take()
+    }
+
+    // Where the last win landed in the high scores, shown with its statistics.
+    pub(crate) fn set_rank(&mut self, rank: Option<usize>) {
+        self.rank = rank;
+    }
+
+    // Both buttons held (or the middle one) chords anywhere, a single click on a number only does
+    // when it's turned on in the settings.
+    fn action(&self, pos: &Position, button: MouseButton, both: bool) -> Option<Action> {
+        let fields = self.board.fields.as_ref();
+        let on_number = fields.is_some_and(|fields| {
+            fields[pos].is_open() && matches!(fields[pos].get_tile(), Tile::Number(_))
+        });
+
+        match button {
+            MouseButton::Middle => fields.map(|_| Action::Chord(ChordButtons::Middle)),
+            _ if both => fields.map(|_| Action::Chord(ChordButtons::LeftRight)),
+            MouseButton::Left if on_number => self
+                .left_click_chords
+                .then_some(Action::Chord(ChordButtons::Left)),
+            MouseButton::Left => Some(Action::Reveal),
+            MouseButton::Right if on_number => {
+                self.right_click_flags_around.then_some(Action::Flag)
+            }
+            MouseButton::Right => Some(Action::Flag),
+        }
+    }
+
+    fn play(&mut self, pos: Position, action: Action) {
+        // A resumed game waits on a paused clock.
+        self.board.time.resume();
+
+        if self.practice {
+            self.assisted = true;
+            self.undo_stack
+                .push((self.board.fields.clone(), self.click_events_start()));
+        }
+
+        match action {
+            Action::Reveal => {
+                if self.clicks.is_empty() {
+                    self.started_at = Some(chrono::Utc::now().timestamp() as u64);
+                }
+                let first = self.board.fields.is_none();
+                self.board.reveal(&pos, true);
+                self.clicks.push(Click::Reveal(pos, self.board.duration()));
+                if first {
+                    self.check_needs_guess();
+                }
+            }
+            Action::Flag => {
+                let question = self.question_marks
+                    && self
+                        .board
+                        .fields
+                        .as_ref()
+                        .is_some_and(|fields| fields[&pos].is_flagged());
+                if question {
+                    self.board.question(&pos, true);
+                    self.clicks
+                        .push(Click::Question(pos, self.board.duration()));
+                } else {
+                    self.board.flag(&pos, true);
+                    self.clicks.push(Click::Flag(pos, self.board.duration()));
+                }
+            }
+            Action::Chord(buttons) => {
+                self.board.chord(&pos, true);
+                self.clicks
+                    .push(Click::Chord(pos, self.board.duration(), buttons));
+            }
+        }
+        self.solution = None;
+        self.hint = None;
+    }
+
+    fn undo(&mut self) {
+        let Some((fields, events_start)) = self.undo_stack.pop() else {
+            return;
+        };
+
+        // The click's own presses and releases go with it, the moves after them are dropped.
+        let mut mouse_events = self
+            .mouse_events
+            .split_off(events_start.min(self.mouse_events.len()));
+        mouse_events.truncate(Self::press_length(&mouse_events));
+        self.redo_stack
+            .push((self.clicks.pop().unwrap(), mouse_events));
+
+        if fields.is_none() {
+            self.board.time.reset();
+        } else if !self.board.time.is_playing() {
+            // Taking back the click that ended the game starts the clock again.
+            let duration = self.board.duration();
+            self.board.time.seek(duration);
+            self.board.time.resume();
+        }
+        if self.clicks.is_empty() {
+            self.started_at = None;
+        }
+
+        self.board.fields = fields;
+        self.solution = None;
+        self.hint = None;
+        self.stats = None;
+        self.won_replay = None;
+    }
+
+    fn redo(&mut self) {
+        if let Some((click, mouse_events)) = self.redo_stack.pop() {
+            let action = match click {
+                Click::Reveal(..) => Action::Reveal,
+                Click::Flag(..) | Click::Question(..) => Action::Flag,
+                Click::Chord(_, _, buttons) => Action::Chord(buttons),
+            };
+            self.play(click.position(), action);
+
+            // The click happens again now, so its mouse events move along with it.
+            let now = self.clicks.last().unwrap().duration();
+            let after = self
+                .mouse_events
+                .last()
+                .map_or(Duration::ZERO, |event| event.duration);
+            self.mouse_events
+                .extend(mouse_events.into_iter().map(|event| {
+                    MouseEvent {
+                        duration: (event.duration + now)
+                            .saturating_sub(click.duration())
+                            .max(after),
+                        ..event
+                    }
+                }));
+        }
+    }
+
+    // Where the mouse events of the click being played start: at the press that's still held, or
+    // at the end when the whole click comes in with this frame's events.
+    fn click_events_start(&self) -> usize {
+        let mut held = 0usize;
+        let mut start = self.mouse_events.len();
+        for (index, event) in self.mouse_events.iter().enumerate() {
+            match event.action {
+                MouseAction::Down(_) => {
+                    if held == 0 {
+                        start = index;
+                    }
+                    held += 1;
+                }
+                MouseAction::Up(_) => held = held.saturating_sub(1),
+                MouseAction::Move => {}
+            }
+        }
+
+        if held > 0 {
+            start
+        } else {
+            self.mouse_events.len()
+        }
+    }
+
+    // How many of the events it takes until every button pressed in them is let go again.
+    fn press_length(mouse_events: &[MouseEvent]) -> usize {
+        let mut held = 0usize;
+        mouse_events
+            .iter()
+            .position(|event| {
+                match event.action {
+                    MouseAction::Down(_) => held += 1,
+                    MouseAction::Up(_) => held = held.saturating_sub(1),
+                    MouseAction::Move => return false,
+                }
+                held == 0
+            })
+            .map_or(mouse_events.len(), |index| index + 1)
     }
 
     pub(crate) fn handle_inputs(&mut self, ctx: &egui::Context) {
@@ -53,24 +564,18 @@ impl PlayBoard {
             self.reset();
         }
 
+        if self.practice && ctx.input(|i| i.modifiers.command && i.key_pressed(Key::Z)) {
+            self.undo();
+        }
+
+        self.handle_mouse(ctx);
+
         if !self.board.still_playing() {
             if ctx.input(|i| i.key_pressed(Key::S)) {
-                if let io::Result::Err(err) = fs::write(
-                    chrono::Utc::now()
-                        .format(
-                            format!(
-                                "minesweeper_{}x{}-{}_{:.3}_%d-%m-%Y_%H-%M-%S.json",
-                                get_size().0,
-                                get_size().1,
-                                get_mine_amount(),
-                                self.board.time.duration().as_secs_f32()
-                            )
-                            .as_str(),
-                        )
-                        .to_string(),
-                    serde_json::to_string(&self.create_replay().unwrap()).unwrap(),
-                ) {
-                    println!("Failed to save game: {:?}", err);
+                let replay_file = self.create_replay().unwrap();
+                let path = replay_file_name(&replay_file, self.replay_format);
+                if let Err(err) = replay_file.save(Path::new(&path)) {
+                    println!("Failed to save game: {}", err);
                 } else {
                     println!("Saved game");
                 }
@@ -79,28 +584,196 @@ impl PlayBoard {
             return;
         }
 
-        if ctx.input(|i| i.pointer.button_pressed(PointerButton::Primary)) {
-            if let Some(pos) =
-                Position::from_mouse(ctx.input(|i| i.pointer.interact_pos().unwrap()))
-            {
-                self.board.reveal(&pos, true);
-                self.clicks
-                    .push(Click::Reveal(pos, self.board.time.duration()));
+        if self.practice
+            && self.board.still_playing()
+            && ctx.input(|i| i.modifiers.command && i.key_pressed(Key::Y))
+        {
+            self.redo();
+        }
+
+        self.record_mouse_events(ctx);
+
+        if ctx.input(|i| i.key_pressed(Key::P)) {
+            self.show_probabilities = !self.show_probabilities;
+        }
+
+        let hint_requested = ctx.input(|i| i.key_pressed(Key::H));
+        if !self.board.still_playing() {
+            self.solution = None;
+            self.hint = None;
+            let replay = self.create_replay();
+            self.stats = replay.as_ref().and_then(|replay| replay.stats);
+            if !self.assisted && self.stats.is_some_and(|stats| stats.won) {
+                self.won_replay = replay;
+            }
+        } else if let Some(fields) = &self.board.fields {
+            if (hint_requested || self.show_probabilities) && self.solution.is_none() {
+                self.solution = Some(Solver::solve_fields(fields));
+            }
+            if hint_requested {
+                self.hint = self.solution.as_ref().and_then(|solution| solution.hint());
+            }
+            // Seeing what the solver knows is help too, like taking moves back.
+            if self.show_probabilities || self.hint.is_some() {
+                self.assisted = true;
             }
         }
+    }
 
-        if ctx.input(|i| i.pointer.button_pressed(PointerButton::Secondary)) {
-            if let Some(pos) =
-                Position::from_mouse(ctx.input(|i| i.pointer.interact_pos().unwrap()))
-            {
-                self.board.flag(&pos, true);
-                self.clicks
-                    .push(Click::Flag(pos, self.board.time.duration()));
+    // Works like classic Minesweeper: a click acts when its button is let go, on whatever cell is
+    // under the pointer then, so dragging off the board cancels it. Letting go of one button while
+    // the other is held (or of the middle one) chords.
+    fn handle_mouse(&mut self, ctx: &egui::Context) {
+        let size = self.board.size;
+        // Walked in order, so letting go of both buttons in the same frame still chords.
+        let (actions, any_down) = ctx.input(|i| {
+            (
+                i.events
+                    .iter()
+                    .filter_map(button_action)
+                    .map(|(action, pos)| (action, Position::from_mouse(pos, size)))
+                    .collect::<Vec<_>>(),
+                i.pointer.any_down(),
+            )
+        });
+
+        for (action, pos) in actions {
+            let Some((button, both)) = self.buttons.handle(action) else {
+                continue;
+            };
+            let Some(pos) = pos.filter(|_| self.board.still_playing()) else {
+                continue;
+            };
+            if let Some(action) = self.action(&pos, button, both) {
+                self.redo_stack.clear();
+                self.play(pos, action);
+            }
+        }
+
+        // A release missed while the settings menu was open mustn't leave a button held.
+        if !any_down {
+            self.buttons = MouseButtons::default();
+        }
+    }
+
+    // The cells drawn pressed in while a button is held: the one under the pointer, or the 3x3
+    // area around it for a chord.
+    fn pressed_cells(&self, ctx: &egui::Context) -> Vec<Position> {
+        let size = self.board.size;
+        let (pos, left, right, middle) = ctx.input(|i| {
+            (
+                i.pointer
+                    .interact_pos()
+                    .and_then(|pos| Position::from_mouse(pos, size)),
+                i.pointer.button_down(PointerButton::Primary),
+                i.pointer.button_down(PointerButton::Secondary),
+                i.pointer.button_down(PointerButton::Middle),
+            )
+        });
+        let Some(pos) = pos.filter(|_| self.board.still_playing() && !self.buttons.chorded())
+        else {
+            return Vec::new();
+        };
+
+        let action = if middle {
+            self.action(&pos, MouseButton::Middle, false)
+        } else if left {
+            self.action(&pos, MouseButton::Left, right)
+        } else {
+            None
+        };
+        let mut cells = match action {
+            Some(Action::Chord(_)) => {
+                let mut cells = pos.neighbors(size);
+                cells.push(pos);
+                cells
             }
+            Some(_) => vec![pos],
+            None => Vec::new(),
+        };
+
+        if let Some(fields) = &self.board.fields {
+            cells.retain(|cell| fields[cell].is_closed() || fields[cell].is_questioned());
         }
+        cells
+    }
+
+    fn record_mouse_events(&mut self, ctx: &egui::Context) {
+        let duration = self.board.duration();
+        ctx.input(|i| {
+            for event in &i.events {
+                let (action, pos) = match *event {
+                    Event::PointerMoved(pos) => (MouseAction::Move, pos),
+                    _ => match button_action(event) {
+                        Some(action) => action,
+                        None => continue,
+                    },
+                };
+
+                // Before the first cell is opened, only the press that's going to open it is kept.
+                if self.board.fields.is_none() {
+                    match action {
+                        MouseAction::Down(_) => self.mouse_events.clear(),
+                        MouseAction::Move if self.mouse_events.is_empty() => continue,
+                        _ => {}
+                    }
+                }
+
+                self.mouse_events
+                    .push(MouseEvent::from_mouse(action, pos, duration));
+            }
+        });
     }
 
     pub(crate) fn draw(&self, ui: &mut egui::Ui) {
         self.board.draw(ui);
+        self.board.draw_pressed(&self.pressed_cells(ui.ctx()), ui);
+
+        if let Some(stats) = &self.stats {
+            draw_stats_window(stats, self.rank, ui.ctx());
+        }
+
+        if self.show_probabilities {
+            if let Some(solution) = &self.solution {
+                for (index, probability) in solution.probabilities.iter().enumerate() {
+                    let Some(probability) = *probability else {
+                        continue;
+                    };
+
+                    let rect = Rect::from_center_size(
+                        Position::from_index(index, self.board.size)
+                            .coordinates()
+                            .into(),
+                        egui::vec2(FIELD_SIZE, FIELD_SIZE),
+                    );
+                    let color = if probability == 0.0 {
+                        Color32::from_rgba_unmultiplied(0, 200, 0, 90)
+                    } else {
+                        Color32::from_rgba_unmultiplied(255, 0, 0, (probability * 150.0) as u8)
+                    };
+
+                    ui.painter().rect_filled(rect, Rounding::none(), color);
+                    ui.painter().text(
+                        rect.center(),
+                        Align2::CENTER_CENTER,
+                        format!("{:.0}", probability * 100.0),
+                        FontId::proportional(FIELD_SIZE * 0.35),
+                        Color32::BLACK,
+                    );
+                }
+            }
+        }
+
+        if let Some(hint) = self.hint {
+            ui.painter().rect_stroke(
+                Rect::from_center_size(
+                    hint.coordinates().into(),
+                    egui::vec2(FIELD_SIZE, FIELD_SIZE),
+                )
+                .shrink(1.5),
+                Rounding::none(),
+                Stroke::new(3.0, Color32::from_rgb(0, 160, 255)),
+            );
+        }
     }
 }
@@ -1,127 +1,208 @@
 use std::{
-    fmt::{self, Display, Formatter},
-    fs, io,
+    path::Path,
     time::{Duration, Instant},
 };
 
 use eframe::{
-    egui::{self, Key, Layout, Slider},
+    egui::{self, Grid, Key, Layout, Slider},
     emath::Align,
-    epaint::Color32,
+    epaint::{Color32, Rect, Stroke},
+};
+use minesweeper_core::{
+    click::Click,
+    field_generator::replay_field_generator::ReplayFieldGenerator,
+    fields::Fields,
+    replay_file::{
+        metadata::{Flagging, GameResult, Generator},
+        signature::{SignatureStatus, PUBLIC_KEY_LENGTH},
+        ReplayFile, ReplayFormat,
+    },
+    stats::{ClickKind, Stats},
 };
 
 use crate::{
     board::Board,
-    click::Click,
-    field_generator::replay_field_generator::ReplayFieldGenerator,
-    get_allow_save_replay, get_mine_amount, get_replay_file, get_size,
-    time::Time,
-    utils::{ease_in_out_quad, lerp},
+    cursor, signing,
+    stats::draw_stats_window,
+    utils::{lerp, replay_file_name},
     CLICK_ANIMATION_DURATION, INNER_CIRCLE_RADIUS, OUTER_CIRCLE_RADIUS,
 };
 
-pub(crate) enum TimeModifier {
-    Slowdown(u32),
-    None,
-    Speedup(u32),
-}
-
-pub(crate) fn get_time_modifier() -> &'static mut TimeModifier {
-    static mut TIME_MODIFIER: TimeModifier = TimeModifier::None;
-    unsafe { &mut TIME_MODIFIER }
-}
+// A copy of the board is kept every this many clicks, so seeking only has to replay the clicks
+// since the closest one.
+const SNAPSHOT_INTERVAL: usize = 32;
 
-impl TimeModifier {
-    pub(crate) fn apply(&self, duration: Duration) -> Duration {
-        match self {
-            TimeModifier::Slowdown(slowdown) => duration / *slowdown,
-            TimeModifier::None => duration,
-            TimeModifier::Speedup(speedup) => duration * *speedup,
-        }
-    }
+// snapshots[i] is the board after the first (i + 1) * SNAPSHOT_INTERVAL clicks.
+fn snapshots(replay_file: &ReplayFile) -> Vec<Option<Fields>> {
+    let mut fields: Option<Fields> = None;
+    let mut snapshots = Vec::with_capacity(replay_file.clicks.len() / SNAPSHOT_INTERVAL);
 
-    pub(crate) fn reset(&mut self) {
-        *self = TimeModifier::None;
-    }
+    for (index, click) in replay_file.clicks.iter().enumerate() {
+        replay_file.apply_click(&mut fields, click);
 
-    fn decrease(&mut self) -> bool {
-        match self {
-            TimeModifier::Slowdown(slowdown) => {
-                if *slowdown < 16 {
-                    *slowdown *= 2;
-                    true
-                } else {
-                    false
-                }
-            }
-            TimeModifier::None => {
-                *self = TimeModifier::Slowdown(2);
-                true
-            }
-            TimeModifier::Speedup(speedup) => {
-                if *speedup <= 2 {
-                    *self = TimeModifier::None;
-                } else {
-                    *speedup /= 2;
-                }
-                true
-            }
+        if (index + 1) % SNAPSHOT_INTERVAL == 0 {
+            snapshots.push(fields.clone());
         }
     }
 
-    fn increase(&mut self) -> bool {
-        match self {
-            TimeModifier::Slowdown(slowdown) => {
-                if *slowdown <= 2 {
-                    *self = TimeModifier::None;
-                } else {
-                    *slowdown /= 2;
-                }
-                true
-            }
-            TimeModifier::None => {
-                *self = TimeModifier::Speedup(2);
-                true
-            }
-            TimeModifier::Speedup(speedup) => {
-                if *speedup < 16 {
-                    *speedup *= 2;
-                    true
-                } else {
-                    false
-                }
-            }
-        }
-    }
+    snapshots
 }
 
-impl Display for TimeModifier {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            TimeModifier::Slowdown(slowdown) => write!(f, "{}x", 1.0 / *slowdown as f32),
-            TimeModifier::None => write!(f, "1x"),
-            TimeModifier::Speedup(speedup) => write!(f, "{}x", speedup),
-        }
-    }
-}
+const MIN_PLAYBACK_RATE: f64 = 0.1;
+const MAX_PLAYBACK_RATE: f64 = 32.0;
 
 pub(crate) struct ReplayBoard {
     board: Board<ReplayFieldGenerator>,
 
+    replay_file: ReplayFile,
+    allow_save_replay: bool,
+    replay_format: ReplayFormat,
+    stats: Stats,
+    click_kinds: Vec<ClickKind>,
+    snapshots: Vec<Option<Fields>>,
+    signature_status: SignatureStatus,
+    trusted_keys: Vec<[u8; PUBLIC_KEY_LENGTH]>,
+
     next_click: usize,
     curr_click_start: Option<Instant>,
 }
 
 impl ReplayBoard {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(replay_file: ReplayFile) -> Self {
+        let mut board = Board::new(
+            ReplayFieldGenerator::new(replay_file.mines.clone()),
+            replay_file.size,
+            replay_file.mines.len(),
+        );
+        board.max_duration = Some(replay_file.duration);
+        let trusted_keys = signing::trusted_keys();
+
         Self {
-            board: Board::new(),
+            board,
+
+            stats: Stats::from_replay(&replay_file),
+            click_kinds: Stats::click_kinds(&replay_file),
+            snapshots: snapshots(&replay_file),
+            signature_status: replay_file.signature_status(&trusted_keys),
+            trusted_keys,
+            replay_file,
+            allow_save_replay: false,
+            replay_format: ReplayFormat::Json,
 
             next_click: 0,
             curr_click_start: None,
         }
     }
 
+    pub(crate) fn set_replay(&mut self, replay_file: ReplayFile, allow_save_replay: bool) {
+        self.board.change_settings(
+            ReplayFieldGenerator::new(replay_file.mines.clone()),
+            replay_file.size,
+            replay_file.mines.len(),
+        );
+        self.board.max_duration = Some(replay_file.duration);
+
+        self.stats = Stats::from_replay(&replay_file);
+        self.click_kinds = Stats::click_kinds(&replay_file);
+        self.snapshots = snapshots(&replay_file);
+        self.signature_status = replay_file.signature_status(&self.trusted_keys);
+        self.replay_file = replay_file;
+        self.board.time.set_rate(1.0);
+        self.allow_save_replay = allow_save_replay;
+
+        self.reset();
+    }
+
+    pub(crate) fn size(&self) -> (usize, usize) {
+        self.board.size
+    }
+
+    pub(crate) fn draw_metadata(&self, ui: &mut egui::Ui) {
+        let replay_file = &self.replay_file;
+        Grid::new("replay_metadata").num_columns(2).show(ui, |ui| {
+            ui.label("Board:");
+            ui.label(format!(
+                "{}x{}, {} mines",
+                replay_file.size.0,
+                replay_file.size.1,
+                replay_file.mines.len()
+            ));
+            ui.end_row();
+
+            ui.label("Seed:");
+            ui.label(
+                replay_file
+                    .seed
+                    .map_or("unknown".to_string(), |seed| seed.to_string()),
+            );
+            ui.end_row();
+
+            if let Some(imported_from) = &replay_file.imported_from {
+                ui.label("Imported from:");
+                ui.label(imported_from);
+                ui.end_row();
+            }
+
+            let Some(metadata) = &replay_file.metadata else {
+                ui.label("Metadata:");
+                ui.label("none, this replay was recorded by an older version");
+                ui.end_row();
+                return;
+            };
+
+            ui.label("Player:");
+            ui.label(&metadata.player);
+            ui.end_row();
+
+            ui.label("Started:");
+            ui.label(
+                chrono::NaiveDateTime::from_timestamp_opt(metadata.started_at as i64, 0)
+                    .map_or("unknown".to_string(), |time| {
+                        time.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+                    }),
+            );
+            ui.end_row();
+
+            ui.label("Game version:");
+            ui.label(&metadata.game_version);
+            ui.end_row();
+
+            ui.label("Board generation:");
+            ui.label(match metadata.generator {
+                Generator::Random => "Random",
+                Generator::Seeded => "Seeded",
+                Generator::NoGuess => "No guessing",
+            });
+            ui.end_row();
+
+            ui.label("Flagging:");
+            ui.label(match metadata.flagging {
+                Flagging::Flags => "Flags",
+                Flagging::NoFlags => "No flags",
+            });
+            ui.end_row();
+
+            ui.label("Result:");
+            ui.label(match metadata.result {
+                GameResult::Won => "Won",
+                GameResult::Lost => "Lost",
+            });
+            ui.end_row();
+
+            ui.label("Assisted:");
+            ui.label(if metadata.assisted {
+                "Yes, played in practice mode"
+            } else {
+                "No"
+            });
+            ui.end_row();
+        });
+    }
+
+    pub(crate) fn set_replay_format(&mut self, replay_format: ReplayFormat) {
+        self.replay_format = replay_format;
+    }
+
     pub(crate) fn reset(&mut self) {
         self.board.reset();
         self.next_click = 0;
@@ -136,39 +217,18 @@ impl ReplayBoard {
         match ctx
             .input(|i| i.key_pressed(Key::ArrowUp) as i32 - i.key_pressed(Key::ArrowDown) as i32)
         {
-            -1 => {
-                if get_time_modifier().decrease() {
-                    self.board.time.modifier_decreased();
-                }
-            }
-            1 => {
-                if get_time_modifier().increase() {
-                    self.board.time.modifier_increased();
-                }
-            }
+            -1 => self.set_playback_rate(self.board.time.rate() / 2.0),
+            1 => self.set_playback_rate(self.board.time.rate() * 2.0),
             _ => {}
         }
 
-        if *get_allow_save_replay() && ctx.input(|i| i.key_pressed(Key::S)) {
-            if let io::Result::Err(err) = fs::write(
-                chrono::Utc::now()
-                    .format(
-                        format!(
-                            "minesweeper_{}x{}-{}_{:.3}_%d-%m-%Y_%H-%M-%S.json",
-                            get_size().0,
-                            get_size().1,
-                            get_mine_amount(),
-                            get_replay_file().duration.as_secs_f32()
-                        )
-                        .as_str(),
-                    )
-                    .to_string(),
-                serde_json::to_string(get_replay_file()).unwrap(),
-            ) {
-                println!("Failed to save game: {:?}", err);
+        if self.allow_save_replay && ctx.input(|i| i.key_pressed(Key::S)) {
+            let path = replay_file_name(&self.replay_file, self.replay_format);
+            if let Err(err) = self.replay_file.save(Path::new(&path)) {
+                println!("Failed to save game: {}", err);
             } else {
                 println!("Saved game");
-                *get_allow_save_replay() = false;
+                self.allow_save_replay = false;
             }
         }
 
@@ -177,20 +237,16 @@ impl ReplayBoard {
         }
 
         if ctx.input(|i| i.key_pressed(Key::Space)) {
-            match self.board.time {
-                Time::None => self.board.time.start(),
-                Time::Playing(_) => self.board.time.pause(),
-                Time::Paused(_) => self.board.time.resume(),
-                _ => unreachable!(),
-            }
+            self.board.time.toggle_pause();
         }
 
         if !self.board.time.is_playing() {
             return;
         }
 
-        let curr_duration = self.board.time.duration();
-        for click in get_replay_file()
+        let curr_duration = self.board.duration();
+        for click in self
+            .replay_file
             .clicks
             .iter()
             .skip(self.next_click)
@@ -199,6 +255,8 @@ impl ReplayBoard {
             match click {
                 Click::Reveal(pos, _) => self.board.reveal(pos, true),
                 Click::Flag(pos, _) => self.board.flag(pos, true),
+                Click::Question(pos, _) => self.board.question(pos, true),
+                Click::Chord(pos, _, _) => self.board.chord(pos, true),
             }
 
             self.next_click += 1;
@@ -206,48 +264,116 @@ impl ReplayBoard {
         }
     }
 
-    pub(crate) fn draw(&mut self, ui: &mut egui::Ui) {
-        self.board.draw(ui);
+    // Puts the board in the state after the first `click` clicks, starting from the closest
+    // snapshot when going backwards or when it's closer than the current click.
+    fn apply_clicks(&mut self, click: usize) {
+        // Replaying the clicks starts and ends the clock, which is set separately.
+        let time = self.board.time;
+
+        let snapshot = click / SNAPSHOT_INTERVAL;
+        if click < self.next_click || snapshot * SNAPSHOT_INTERVAL > self.next_click {
+            match snapshot
+                .checked_sub(1)
+                .and_then(|index| self.snapshots.get(index))
+            {
+                Some(fields) => {
+                    self.board.fields = fields.clone();
+                    self.next_click = snapshot * SNAPSHOT_INTERVAL;
+                }
+                None => {
+                    self.board.reset();
+                    self.next_click = 0;
+                }
+            }
+        }
 
-        let click_pos = match (
-            self.next_click,
-            get_replay_file().clicks.get(self.next_click),
-        ) {
-            (0, Some(click)) => click.position().coordinates(),
-            (_, Some(click)) => {
-                let pos = click.position().coordinates();
-                let (prev_pos, prev_duration) = match get_replay_file().clicks[self.next_click - 1]
-                {
-                    Click::Reveal(pos, duration) | Click::Flag(pos, duration) => {
-                        (pos.coordinates(), duration)
-                    }
-                };
+        for click in &self.replay_file.clicks[self.next_click..click] {
+            match click {
+                Click::Reveal(pos, _) => self.board.reveal(pos, false),
+                Click::Flag(pos, _) => self.board.flag(pos, false),
+                Click::Question(pos, _) => self.board.question(pos, false),
+                Click::Chord(pos, _, _) => self.board.chord(pos, false),
+            }
+        }
+
+        self.next_click = click;
+        self.curr_click_start = None;
+        self.board.time = time;
+    }
 
-                let curr_duration = self.board.time.duration();
+    fn set_time(&mut self, duration: Duration) {
+        if !self.board.still_playing() {
+            self.board.time.end_at(self.replay_file.duration);
+        } else if self.board.fields.is_none() && duration.is_zero() && !self.board.time.is_playing()
+        {
+            self.board.time.reset();
+        } else {
+            self.board.time.seek(duration);
+        }
+    }
 
-                let click_progress = if curr_duration > prev_duration {
-                    (curr_duration - prev_duration).as_secs_f32()
-                        / (click.duration() - prev_duration).as_secs_f32()
-                } else {
-                    0.0
-                };
+    fn set_playback_rate(&mut self, rate: f64) {
+        self.board
+            .time
+            .set_rate(rate.clamp(MIN_PLAYBACK_RATE, MAX_PLAYBACK_RATE));
+    }
 
-                (
-                    lerp(prev_pos.0, pos.0, ease_in_out_quad(click_progress)),
-                    lerp(prev_pos.1, pos.1, ease_in_out_quad(click_progress)),
-                )
-            }
-            (_, None) => get_replay_file()
-                .clicks
-                .last()
-                .unwrap()
-                .position()
-                .coordinates(),
-        };
+    fn seek(&mut self, duration: Duration) {
+        let duration = duration.min(self.replay_file.duration);
+        let click = self
+            .replay_file
+            .clicks
+            .partition_point(|click| click.duration() <= duration);
+
+        self.apply_clicks(click);
+        self.set_time(duration);
+    }
+
+    fn step_to_click(&mut self, click: usize) {
+        self.apply_clicks(click);
+        self.set_time(match click {
+            0 => Duration::ZERO,
+            click => self.replay_file.clicks[click - 1].duration(),
+        });
+    }
+
+    fn draw_click_ticks(&self, ui: &egui::Ui, rect: Rect) {
+        let total = self.replay_file.duration.as_secs_f32();
+        if total <= 0.0 {
+            return;
+        }
+
+        // The slider keeps its handle inside the rect, so its rail is shorter than the rect.
+        let handle_radius = rect.height() / 2.5;
+        let (left, right) = (rect.left() + handle_radius, rect.right() - handle_radius);
+        let (top, bottom) = (rect.center().y + rect.height() / 4.0, rect.bottom());
+
+        for (click, kind) in self.replay_file.clicks.iter().zip(&self.click_kinds) {
+            let x = lerp(left, right, click.duration().as_secs_f32() / total);
+            ui.painter().line_segment(
+                [egui::pos2(x, top), egui::pos2(x, bottom)],
+                Stroke::new(
+                    1.0,
+                    match kind {
+                        ClickKind::Left => Color32::LIGHT_BLUE,
+                        ClickKind::Right | ClickKind::Question => Color32::LIGHT_RED,
+                        ClickKind::Chord => Color32::GOLD,
+                    },
+                ),
+            );
+        }
+    }
+
+    pub(crate) fn draw(&mut self, ui: &mut egui::Ui) {
+        self.board.draw(ui);
+
+        let (click_pos, pressed) =
+            cursor::position(&self.replay_file, self.next_click, self.board.duration());
 
         ui.painter().circle_filled(
             click_pos.into(),
             match self.curr_click_start {
+                _ if pressed => INNER_CIRCLE_RADIUS,
                 Some(start) => {
                     let elapsed = start.elapsed();
                     if elapsed >= CLICK_ANIMATION_DURATION {
@@ -276,95 +402,117 @@ impl ReplayBoard {
             ui.vertical(|ui| {
                 let width = ui.available_width();
                 ui.style_mut().spacing.slider_width = width;
-                let prev = self.next_click;
-                if ui
-                    .add(
-                        Slider::new(&mut self.next_click, 0..=get_replay_file().clicks.len())
-                            .trailing_fill(true)
-                            .show_value(false),
-                    )
-                    .changed()
-                    || ui.ctx().input(|i| {
-                        let left = i.key_pressed(Key::ArrowLeft) && self.next_click > 0;
-                        if left {
-                            self.next_click -= 1;
-                        }
-                        let right = i.key_pressed(Key::ArrowRight)
-                            && self.next_click < get_replay_file().clicks.len();
-                        if right {
-                            self.next_click += 1;
-                        }
-                        left ^ right
-                    })
-                {
-                    match self.next_click {
-                        0 => self.board.reset(),
-                        click if click == get_replay_file().clicks.len() => {
-                            if click != prev {
-                                for click in get_replay_file().clicks.iter().skip(prev) {
-                                    match click {
-                                        Click::Reveal(pos, _) => self.board.reveal(pos, false),
-                                        Click::Flag(pos, _) => self.board.flag(pos, false),
-                                    }
-                                }
-
-                                self.curr_click_start = None;
-                            }
-                            self.board.time =
-                                Time::Ended(get_time_modifier().apply(get_replay_file().duration));
-                        }
-                        click => {
-                            if click > prev {
-                                for click in get_replay_file()
-                                    .clicks
-                                    .iter()
-                                    .skip(prev)
-                                    .take(click - prev)
-                                {
-                                    match click {
-                                        Click::Reveal(pos, _) => self.board.reveal(pos, false),
-                                        Click::Flag(pos, _) => self.board.flag(pos, false),
-                                    }
-                                }
-                            } else {
-                                self.board.reset();
-                                for click in get_replay_file().clicks.iter().take(click) {
-                                    match click {
-                                        Click::Reveal(pos, _) => self.board.reveal(pos, false),
-                                        Click::Flag(pos, _) => self.board.flag(pos, false),
-                                    }
-                                }
-                            }
-
-                            self.curr_click_start = None;
-                            self.board.time = Time::Paused(
-                                get_time_modifier()
-                                    .apply(get_replay_file().clicks[click - 1].duration()),
-                            );
-                        }
+                let mut seconds = self.board.duration().as_secs_f32();
+                let slider = ui.add(
+                    Slider::new(&mut seconds, 0.0..=self.replay_file.duration.as_secs_f32())
+                        .trailing_fill(true)
+                        .show_value(false),
+                );
+                if slider.changed() {
+                    self.seek(Duration::from_secs_f32(seconds));
+                    ui.ctx().request_repaint();
+                }
+                self.draw_click_ticks(ui, slider.rect);
+
+                let (click_step, jump) = ui.ctx().input(|i| {
+                    let direction = i.key_pressed(Key::ArrowRight) as i32
+                        - i.key_pressed(Key::ArrowLeft) as i32;
+                    if i.modifiers.command {
+                        (0, direction * 5)
+                    } else if i.modifiers.shift {
+                        (0, direction)
+                    } else {
+                        (direction, 0)
                     }
-
+                });
+                if click_step != 0 {
+                    self.step_to_click(
+                        self.next_click
+                            .saturating_add_signed(click_step as isize)
+                            .min(self.replay_file.clicks.len()),
+                    );
+                    ui.ctx().request_repaint();
+                }
+                if jump != 0 {
+                    let offset = Duration::from_secs(jump.unsigned_abs() as u64);
+                    let duration = self.board.duration();
+                    self.seek(if jump > 0 {
+                        duration + offset
+                    } else {
+                        duration.saturating_sub(offset)
+                    });
                     ui.ctx().request_repaint();
                 }
 
                 ui.horizontal(|ui| {
                     ui.label(format!(
-                        "{} {:.3}/{:.3}",
-                        get_time_modifier(),
-                        self.board.time.duration().as_secs_f32(),
-                        get_replay_file().duration.as_secs_f32()
+                        "{:.3}/{:.3}",
+                        self.board.duration().as_secs_f32(),
+                        self.replay_file.duration.as_secs_f32()
                     ));
+
+                    ui.style_mut().spacing.slider_width = 100.0;
+                    let mut rate = self.board.time.rate();
+                    if ui
+                        .add(
+                            Slider::new(&mut rate, MIN_PLAYBACK_RATE..=MAX_PLAYBACK_RATE)
+                                .logarithmic(true)
+                                .max_decimals(2)
+                                .suffix("x"),
+                        )
+                        .changed()
+                    {
+                        self.set_playback_rate(rate);
+                    }
+
                     ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
                         ui.label(format!(
                             "{}/{}",
                             self.next_click,
-                            get_replay_file().clicks.len()
+                            self.replay_file.clicks.len()
                         ));
                     });
                 });
+
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "3BV {}/{}  3BV/s {:.3}  IOE {:.3}",
+                        self.stats.solved_bbbv,
+                        self.stats.bbbv,
+                        self.stats.bbbv_per_second(),
+                        self.stats.ioe()
+                    ));
+                    ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                        match (&self.replay_file.imported_from, self.signature_status) {
+                            (Some(imported_from), _) => ui.label(imported_from),
+                            (None, SignatureStatus::Signed) => ui.label("Signed"),
+                            (None, SignatureStatus::OtherKey) => {
+                                ui.colored_label(Color32::YELLOW, "Signed by another key")
+                            }
+                            (None, SignatureStatus::Unsigned) => {
+                                ui.colored_label(Color32::YELLOW, "Unsigned")
+                            }
+                            (None, SignatureStatus::Tampered) => {
+                                ui.colored_label(Color32::RED, "Tampered")
+                            }
+                        };
+                        if self
+                            .replay_file
+                            .metadata
+                            .as_ref()
+                            .is_some_and(|metadata| metadata.assisted)
+                        {
+                            ui.colored_label(Color32::LIGHT_BLUE, "Assisted");
+                        }
+                    });
+                });
             });
         });
 
+        if self.next_click == self.replay_file.clicks.len() {
+            draw_stats_window(&self.stats, None, ui.ctx());
+        }
+
         if self.board.time.is_playing() {
             ui.ctx().request_repaint();
         }
@@ -4,19 +4,29 @@ use eframe::{
 };
 use egui_extras::{image::FitTo, RetainedImage};
 
-use crate::{get_size, BORDER_HEIGHT, BORDER_WIDTH, FIELD_SIZE, NUMBER_HEIGHT, NUMBER_MARGIN};
+use crate::{BORDER_HEIGHT, BORDER_WIDTH, FIELD_SIZE, NUMBER_HEIGHT, NUMBER_MARGIN};
 
 const TOP_LEFT: &str = include_str!("../assets/top_left.svg");
 const TOP_RIGHT: &str = include_str!("../assets/top_right.svg");
 const BOTTOM_LEFT: &str = include_str!("../assets/bottom_left.svg");
 const BOTTOM_RIGHT: &str = include_str!("../assets/bottom_right.svg");
 
-const HORIZONTAL: &str = include_str!("../assets/horizontal.svg");
-const VERTICAL: &str = include_str!("../assets/vertical.svg");
+pub(crate) const HORIZONTAL: &str = include_str!("../assets/horizontal.svg");
+pub(crate) const VERTICAL: &str = include_str!("../assets/vertical.svg");
 
 const T_LEFT: &str = include_str!("../assets/t_left.svg");
 const T_RIGHT: &str = include_str!("../assets/t_right.svg");
 
+// In the order the corners are indexed when drawing.
+pub(crate) const CORNER_SVGS: [&str; 6] = [
+    TOP_LEFT,
+    TOP_RIGHT,
+    BOTTOM_LEFT,
+    BOTTOM_RIGHT,
+    T_LEFT,
+    T_RIGHT,
+];
+
 pub(crate) struct Border {
     border_textures: Vec<RetainedImage>,
 }
@@ -24,45 +34,38 @@ pub(crate) struct Border {
 impl Border {
     pub(crate) fn new() -> Self {
         Self {
-            border_textures: [
-                TOP_LEFT,
-                TOP_RIGHT,
-                BOTTOM_LEFT,
-                BOTTOM_RIGHT,
-                T_LEFT,
-                T_RIGHT,
-            ]
-            .iter()
-            .map(|svg| {
-                RetainedImage::from_svg_bytes_with_size(
-                    "I have no debug name unfortunately",
-                    svg.as_bytes(),
-                    FitTo::Size(BORDER_WIDTH as u32, BORDER_HEIGHT as u32),
-                )
-                .unwrap()
-            })
-            .chain(
-                [
+            border_textures: CORNER_SVGS
+                .iter()
+                .map(|svg| {
                     RetainedImage::from_svg_bytes_with_size(
-                        "I have a debug name: horizontal",
-                        HORIZONTAL.as_bytes(),
-                        FitTo::Size(1, BORDER_HEIGHT as u32),
+                        "I have no debug name unfortunately",
+                        svg.as_bytes(),
+                        FitTo::Size(BORDER_WIDTH as u32, BORDER_HEIGHT as u32),
                     )
-                    .unwrap(),
-                    RetainedImage::from_svg_bytes_with_size(
-                        "I have a debug name: vertical",
-                        VERTICAL.as_bytes(),
-                        FitTo::Size(BORDER_WIDTH as u32, 1),
-                    )
-                    .unwrap(),
-                ]
-                .into_iter(),
-            )
-            .collect(),
+                    .unwrap()
+                })
+                .chain(
+                    [
+                        RetainedImage::from_svg_bytes_with_size(
+                            "I have a debug name: horizontal",
+                            HORIZONTAL.as_bytes(),
+                            FitTo::Size(1, BORDER_HEIGHT as u32),
+                        )
+                        .unwrap(),
+                        RetainedImage::from_svg_bytes_with_size(
+                            "I have a debug name: vertical",
+                            VERTICAL.as_bytes(),
+                            FitTo::Size(BORDER_WIDTH as u32, 1),
+                        )
+                        .unwrap(),
+                    ]
+                    .into_iter(),
+                )
+                .collect(),
         }
     }
 
-    pub(crate) fn draw(&self, ui: &mut egui::Ui) {
+    pub(crate) fn draw(&self, size: (usize, usize), ui: &mut egui::Ui) {
         /* #region Draw border */
 
         /* #region Draw horizontal stripes */
@@ -75,7 +78,7 @@ impl Border {
             Rect::from_min_size(
                 egui::pos2(0.0, 0.0),
                 egui::vec2(
-                    BORDER_WIDTH * 2.0 + FIELD_SIZE * get_size().0 as f32,
+                    BORDER_WIDTH * 2.0 + FIELD_SIZE * size.0 as f32,
                     BORDER_HEIGHT,
                 ),
             ),
@@ -89,7 +92,7 @@ impl Border {
             Rect::from_min_size(
                 egui::pos2(0.0, BORDER_HEIGHT + NUMBER_MARGIN * 2.0 + NUMBER_HEIGHT),
                 egui::vec2(
-                    BORDER_WIDTH * 2.0 + FIELD_SIZE * get_size().0 as f32,
+                    BORDER_WIDTH * 2.0 + FIELD_SIZE * size.0 as f32,
                     BORDER_HEIGHT,
                 ),
             ),
@@ -106,10 +109,10 @@ impl Border {
                     BORDER_HEIGHT * 2.0
                         + NUMBER_MARGIN * 2.0
                         + NUMBER_HEIGHT
-                        + FIELD_SIZE * get_size().1 as f32,
+                        + FIELD_SIZE * size.1 as f32,
                 ),
                 egui::vec2(
-                    BORDER_WIDTH * 2.0 + FIELD_SIZE * get_size().0 as f32,
+                    BORDER_WIDTH * 2.0 + FIELD_SIZE * size.0 as f32,
                     BORDER_HEIGHT,
                 ),
             ),
@@ -130,7 +133,7 @@ impl Border {
                     BORDER_HEIGHT * 3.0
                         + NUMBER_MARGIN * 2.0
                         + NUMBER_HEIGHT
-                        + FIELD_SIZE * get_size().0 as f32,
+                        + FIELD_SIZE * size.0 as f32,
                 ),
             ),
         );
@@ -141,13 +144,13 @@ impl Border {
         .paint_at(
             ui,
             Rect::from_min_size(
-                egui::pos2(BORDER_WIDTH + FIELD_SIZE * get_size().0 as f32, 0.0),
+                egui::pos2(BORDER_WIDTH + FIELD_SIZE * size.0 as f32, 0.0),
                 egui::vec2(
                     BORDER_WIDTH,
                     BORDER_HEIGHT * 3.0
                         + NUMBER_MARGIN * 2.0
                         + NUMBER_HEIGHT
-                        + FIELD_SIZE * get_size().0 as f32,
+                        + FIELD_SIZE * size.0 as f32,
                 ),
             ),
         );
@@ -172,7 +175,7 @@ impl Border {
         .paint_at(
             ui,
             Rect::from_min_size(
-                egui::pos2(BORDER_WIDTH + FIELD_SIZE * get_size().0 as f32, 0.0),
+                egui::pos2(BORDER_WIDTH + FIELD_SIZE * size.0 as f32, 0.0),
                 egui::vec2(BORDER_WIDTH, BORDER_HEIGHT),
             ),
         );
@@ -188,7 +191,7 @@ impl Border {
                     BORDER_HEIGHT * 2.0
                         + NUMBER_MARGIN * 2.0
                         + NUMBER_HEIGHT
-                        + FIELD_SIZE * get_size().1 as f32,
+                        + FIELD_SIZE * size.1 as f32,
                 ),
                 egui::vec2(BORDER_WIDTH, BORDER_HEIGHT),
             ),
@@ -201,11 +204,11 @@ impl Border {
             ui,
             Rect::from_min_size(
                 egui::pos2(
-                    BORDER_WIDTH + FIELD_SIZE * get_size().0 as f32,
+                    BORDER_WIDTH + FIELD_SIZE * size.0 as f32,
                     BORDER_HEIGHT * 2.0
                         + NUMBER_MARGIN * 2.0
                         + NUMBER_HEIGHT
-                        + FIELD_SIZE * get_size().1 as f32,
+                        + FIELD_SIZE * size.1 as f32,
                 ),
                 egui::vec2(BORDER_WIDTH, BORDER_HEIGHT),
             ),
@@ -230,7 +233,7 @@ impl Border {
             ui,
             Rect::from_min_size(
                 egui::pos2(
-                    BORDER_WIDTH + FIELD_SIZE * get_size().0 as f32,
+                    BORDER_WIDTH + FIELD_SIZE * size.0 as f32,
                     BORDER_HEIGHT + NUMBER_MARGIN * 2.0 + NUMBER_HEIGHT,
                 ),
                 egui::vec2(BORDER_WIDTH, BORDER_HEIGHT),
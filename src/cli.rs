@@ -0,0 +1,265 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use minesweeper_core::{
+    replay_file::{signature::SignatureStatus, ReplayFile, ReplayFormat},
+    stats::Stats,
+};
+
+use crate::{
+    render::{self, animation},
+    signing,
+};
+
+const USAGE: &str = "Usage:
+  minesweeper [--seed <seed>] [replay]   Open the game, or a replay
+  minesweeper validate <file>            Check that a replay is valid
+  minesweeper stats <file>               Print the statistics of a replay
+  minesweeper convert <in> <out>         Convert a replay, the formats are picked from the extensions
+  minesweeper info <file or directory>.. Print a summary line for every replay
+  minesweeper render <replay> <out> [--fps <fps>]
+                                         Render a replay to a GIF, or to PNG frames if <out> isn't a .gif
+  minesweeper image <replay> <out> [--click <n>] [--reveal-mines]
+                                         Draw the board of a replay, after n clicks, to a PNG or SVG";
+
+const DEFAULT_FPS: u32 = 25;
+
+// Returns the exit code if the arguments were a command, or None to open the window.
+pub(crate) fn run(args: &[String]) -> Option<i32> {
+    let (command, args) = args.split_first()?;
+
+    let result = match (command.as_str(), args) {
+        ("validate", [file]) => validate(Path::new(file)),
+        ("stats", [file]) => stats(Path::new(file)),
+        ("convert", [input, output]) => convert(Path::new(input), Path::new(output)),
+        ("info", paths) if !paths.is_empty() => info(paths),
+        ("render", [input, output]) => render(Path::new(input), Path::new(output), DEFAULT_FPS),
+        ("render", [input, output, flag, fps]) if flag == "--fps" => match fps.parse() {
+            Ok(fps) => render(Path::new(input), Path::new(output), fps),
+            Err(_) => Err(format!("Invalid fps: {}", fps)),
+        },
+        ("image", [input, output, options @ ..]) => {
+            image(Path::new(input), Path::new(output), options)
+        }
+        ("help" | "--help" | "-h", []) => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        ("validate" | "stats" | "convert" | "info" | "render" | "image" | "help", _) => {
+            Err(USAGE.to_string())
+        }
+        _ => return None,
+    };
+
+    match result {
+        Ok(()) => Some(0),
+        Err(err) => {
+            eprintln!("{}", err);
+            Some(1)
+        }
+    }
+}
+
+fn load(path: &Path) -> Result<(ReplayFile, Stats), String> {
+    let replay_file = ReplayFile::load(path)?;
+    let stats = Stats::from_replay(&replay_file);
+
+    Ok((replay_file, stats))
+}
+
+fn validate(path: &Path) -> Result<(), String> {
+    let (replay_file, stats) = load(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+
+    println!(
+        "{}: valid, {} in {:.3}s, {}{}",
+        path.display(),
+        if stats.won { "won" } else { "lost" },
+        replay_file.duration.as_secs_f32(),
+        match replay_file.signature_status(&signing::trusted_keys()) {
+            SignatureStatus::Signed => "signed",
+            SignatureStatus::OtherKey => "signed by another key",
+            SignatureStatus::Unsigned => "unsigned",
+            SignatureStatus::Tampered => "tampered",
+        },
+        if replay_file
+            .metadata
+            .as_ref()
+            .is_some_and(|metadata| metadata.assisted)
+        {
+            ", assisted"
+        } else {
+            ""
+        }
+    );
+
+    Ok(())
+}
+
+fn stats(path: &Path) -> Result<(), String> {
+    let (replay_file, stats) = load(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+
+    println!(
+        "Board: {}x{}, {} mines",
+        replay_file.size.0,
+        replay_file.size.1,
+        replay_file.mines.len()
+    );
+    println!("Result: {}", if stats.won { "Won" } else { "Lost" });
+    println!("Time: {:.3}", stats.duration.as_secs_f32());
+    println!("3BV: {}/{}", stats.solved_bbbv, stats.bbbv);
+    println!("3BV/s: {:.3}", stats.bbbv_per_second());
+    println!(
+        "Clicks: {} ({} effective, {} wasted)",
+        stats.clicks(),
+        stats.effective_clicks(),
+        stats.wasted_clicks()
+    );
+    for (name, click_stats) in [
+        ("Left", stats.left),
+        ("Right", stats.right),
+        ("Chord", stats.chord),
+    ] {
+        println!(
+            "{}: {} ({} effective, {} wasted)",
+            name,
+            click_stats.total,
+            click_stats.effective,
+            click_stats.wasted()
+        );
+    }
+    if stats.questions > 0 {
+        println!("Question marks: {}", stats.questions);
+    }
+    println!("IOE: {:.3}", stats.ioe());
+    println!("Throughput: {:.3}", stats.throughput());
+    println!("Correctness: {:.3}", stats.correctness());
+
+    Ok(())
+}
+
+fn convert(input: &Path, output: &Path) -> Result<(), String> {
+    let (replay_file, _) = load(input).map_err(|err| format!("{}: {}", input.display(), err))?;
+
+    replay_file
+        .save(output)
+        .map_err(|err| format!("{}: {}", output.display(), err))?;
+    println!("{} -> {}", input.display(), output.display());
+
+    Ok(())
+}
+
+fn info(paths: &[String]) -> Result<(), String> {
+    let mut files = Vec::new();
+    for path in paths.iter().map(PathBuf::from) {
+        if path.is_dir() {
+            let mut entries: Vec<PathBuf> = fs::read_dir(&path)
+                .map_err(|err| format!("{}: {}", path.display(), err))?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.is_file()
+                        && path
+                            .extension()
+                            .and_then(|extension| extension.to_str())
+                            .and_then(ReplayFormat::from_extension)
+                            .is_some_and(|format| format != ReplayFormat::Raw)
+                })
+                .collect();
+            entries.sort();
+            files.extend(entries);
+        } else {
+            files.push(path);
+        }
+    }
+
+    let mut failed = 0;
+    for file in &files {
+        match load(file) {
+            Ok((replay_file, stats)) => println!(
+                "{}\t{}x{}/{}\t{}\t{:.3}s\t3BV {}/{}\t3BV/s {:.3}",
+                file.display(),
+                replay_file.size.0,
+                replay_file.size.1,
+                replay_file.mines.len(),
+                if stats.won { "won" } else { "lost" },
+                stats.duration.as_secs_f32(),
+                stats.solved_bbbv,
+                stats.bbbv,
+                stats.bbbv_per_second()
+            ),
+            Err(err) => {
+                failed += 1;
+                println!("{}\terror\t{}", file.display(), err);
+            }
+        }
+    }
+
+    if failed > 0 {
+        Err(format!(
+            "{} of {} replays couldn't be read",
+            failed,
+            files.len()
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn render(input: &Path, output: &Path, fps: u32) -> Result<(), String> {
+    let (replay_file, _) = load(input).map_err(|err| format!("{}: {}", input.display(), err))?;
+    let gif = output
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("gif"));
+
+    if fps == 0 {
+        return Err("The fps has to be at least 1".to_string());
+    }
+    if gif && fps > animation::MAX_GIF_FPS {
+        return Err(format!(
+            "GIFs can't go above {} fps",
+            animation::MAX_GIF_FPS
+        ));
+    }
+
+    let frames = if gif {
+        animation::render_gif(&replay_file, fps, output)
+    } else {
+        animation::render_png_frames(&replay_file, fps, output)
+    }
+    .map_err(|err| format!("{}: {}", output.display(), err))?;
+    println!(
+        "{} -> {} ({} frames)",
+        input.display(),
+        output.display(),
+        frames
+    );
+
+    Ok(())
+}
+
+fn image(input: &Path, output: &Path, options: &[String]) -> Result<(), String> {
+    let (replay_file, _) = load(input).map_err(|err| format!("{}: {}", input.display(), err))?;
+
+    let mut click = replay_file.clicks.len();
+    let mut reveal_mines = false;
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--click" => {
+                let value = options.next().ok_or_else(|| USAGE.to_string())?;
+                click = value
+                    .parse()
+                    .map_err(|_| format!("Invalid click: {}", value))?;
+            }
+            "--reveal-mines" => reveal_mines = true,
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+
+    render::render_replay_image(&replay_file, click, reveal_mines, output)
+        .map_err(|err| format!("{}: {}", output.display(), err))?;
+    println!("{} -> {}", input.display(), output.display());
+
+    Ok(())
+}
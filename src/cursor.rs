@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use minesweeper_core::{
+    mouse_event::{MouseAction, MouseEvent},
+    replay_file::ReplayFile,
+};
+
+use crate::{
+    mouse_event::ScreenMouseEvent,
+    position::ScreenPosition,
+    utils::{ease_in_out_quad, lerp},
+};
+
+// Where the cursor of a replay is on screen after `next_click` clicks, and whether a button is held.
+pub(crate) fn position(
+    replay_file: &ReplayFile,
+    next_click: usize,
+    duration: Duration,
+) -> ((f32, f32), bool) {
+    if replay_file.mouse_events.is_empty() {
+        (interpolated(replay_file, next_click, duration), false)
+    } else {
+        recorded(&replay_file.mouse_events, duration)
+    }
+}
+
+fn interpolated(replay_file: &ReplayFile, next_click: usize, duration: Duration) -> (f32, f32) {
+    match (next_click, replay_file.clicks.get(next_click)) {
+        (0, Some(click)) => click.position().coordinates(),
+        (_, Some(click)) => {
+            let pos = click.position().coordinates();
+            let prev = &replay_file.clicks[next_click - 1];
+            let (prev_pos, prev_duration) = (prev.position().coordinates(), prev.duration());
+
+            let click_progress = if duration > prev_duration {
+                (duration - prev_duration).as_secs_f32()
+                    / (click.duration() - prev_duration).as_secs_f32()
+            } else {
+                0.0
+            };
+
+            (
+                lerp(prev_pos.0, pos.0, ease_in_out_quad(click_progress)),
+                lerp(prev_pos.1, pos.1, ease_in_out_quad(click_progress)),
+            )
+        }
+        (_, None) => replay_file.clicks.last().unwrap().position().coordinates(),
+    }
+}
+
+fn recorded(events: &[MouseEvent], duration: Duration) -> ((f32, f32), bool) {
+    let next = events.partition_point(|event| event.duration <= duration);
+    let pos = match (
+        next.checked_sub(1).map(|prev| &events[prev]),
+        events.get(next),
+    ) {
+        (Some(prev), Some(event)) => {
+            let progress = (duration - prev.duration).as_secs_f32()
+                / (event.duration - prev.duration).as_secs_f32();
+            let (prev_pos, pos) = (prev.coordinates(), event.coordinates());
+
+            (
+                lerp(prev_pos.0, pos.0, progress),
+                lerp(prev_pos.1, pos.1, progress),
+            )
+        }
+        (Some(event), None) | (None, Some(event)) => event.coordinates(),
+        (None, None) => unreachable!(),
+    };
+
+    let mut held = [false; 3];
+    for event in &events[..next] {
+        match event.action {
+            MouseAction::Move => {}
+            MouseAction::Down(button) => held[button as usize] = true,
+            MouseAction::Up(button) => held[button as usize] = false,
+        }
+    }
+
+    (pos, held.contains(&true))
+}
@@ -0,0 +1,159 @@
+use std::{fs, path::PathBuf};
+
+use eframe::egui::{self, ComboBox, Grid};
+use minesweeper_core::{
+    leaderboard::{Category, Entry, Leaderboard},
+    replay_file::{ReplayFile, ReplayFormat},
+};
+
+use crate::utils::replay_file_name;
+
+pub(crate) fn data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("minesweeper")
+}
+
+fn leaderboard_path() -> PathBuf {
+    data_dir().join("leaderboard.json")
+}
+
+fn replays_dir() -> PathBuf {
+    data_dir().join("replays")
+}
+
+fn category_name(category: &Category) -> String {
+    format!(
+        "{}x{}, {} mines{}{}",
+        category.size.0,
+        category.size.1,
+        category.mines,
+        if category.no_guess {
+            ", no guessing"
+        } else {
+            ""
+        },
+        if category.flagging { "" } else { ", no flags" }
+    )
+}
+
+pub(crate) struct LeaderboardScreen {
+    leaderboard: Leaderboard,
+    category: Option<Category>,
+}
+
+impl LeaderboardScreen {
+    pub(crate) fn new() -> Self {
+        let leaderboard = Leaderboard::load(&leaderboard_path()).unwrap_or_else(|err| {
+            println!("Couldn't load leaderboard: {}", err);
+            Leaderboard::default()
+        });
+
+        Self {
+            leaderboard,
+            category: None,
+        }
+    }
+
+    // Returns the rank if the game made it into the high scores.
+    pub(crate) fn record_win(&mut self, replay_file: &ReplayFile, no_guess: bool) -> Option<usize> {
+        let stats = replay_file.stats?;
+        if !stats.won {
+            return None;
+        }
+
+        let replay_path = replays_dir().join(replay_file_name(
+            replay_file,
+            ReplayFormat::CompressedBinary,
+        ));
+        let replay = match fs::create_dir_all(replays_dir())
+            .map_err(|e| e.to_string())
+            .and_then(|_| replay_file.save(&replay_path))
+        {
+            Ok(_) => Some(replay_path),
+            Err(err) => {
+                println!("Failed to save game: {}", err);
+                None
+            }
+        };
+
+        let category = Category {
+            size: replay_file.size,
+            mines: replay_file.mines.len(),
+            no_guess,
+            flagging: replay_file.flagged(),
+        };
+
+        let rank = self.leaderboard.add(Entry {
+            category,
+            duration: replay_file.duration,
+            bbbv_per_second: stats.bbbv_per_second(),
+            date: chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
+            replay,
+        });
+
+        if rank.is_some() {
+            self.category = Some(category);
+
+            if let Err(err) = self.leaderboard.save(&leaderboard_path()) {
+                println!("Failed to save leaderboard: {}", err);
+            }
+        }
+
+        rank
+    }
+
+    pub(crate) fn draw(&mut self, ui: &mut egui::Ui) -> Option<PathBuf> {
+        let categories = self.leaderboard.categories();
+        if categories.is_empty() {
+            ui.label("No games won yet.");
+            return None;
+        }
+
+        if !self
+            .category
+            .is_some_and(|category| categories.contains(&category))
+        {
+            self.category = Some(categories[0]);
+        }
+        let mut selected = self.category.unwrap();
+
+        ComboBox::from_label("Category")
+            .selected_text(category_name(&selected))
+            .show_ui(ui, |ui| {
+                for category in &categories {
+                    ui.selectable_value(&mut selected, *category, category_name(category));
+                }
+            });
+        self.category = Some(selected);
+
+        ui.separator();
+
+        let mut open = None;
+        Grid::new("leaderboard").striped(true).show(ui, |ui| {
+            ui.label("#");
+            ui.label("Time");
+            ui.label("3BV/s");
+            ui.label("Date");
+            ui.label("");
+            ui.end_row();
+
+            for (rank, entry) in self.leaderboard.entries(&selected).iter().enumerate() {
+                ui.label(format!("{}", rank + 1));
+                ui.label(format!("{:.3}", entry.duration.as_secs_f32()));
+                ui.label(format!("{:.3}", entry.bbbv_per_second));
+                ui.label(&entry.date);
+                if let Some(replay) = &entry.replay {
+                    if ui.button("Replay").clicked() {
+                        open = Some(replay.clone());
+                    }
+                } else {
+                    ui.label("");
+                }
+                ui.end_row();
+            }
+        });
+
+        open
+    }
+}
@@ -2,33 +2,40 @@
 
 mod board;
 mod border;
-mod click;
+mod cli;
+mod cursor;
 mod field;
-mod field_generator;
 mod fields;
-mod number;
+mod leaderboard;
+mod mouse_event;
+mod player;
 mod position;
-mod replay_file;
+mod render;
+mod saved_game;
 mod seven_segment_number;
+mod signing;
 mod sounds;
+mod stats;
 mod textures;
-mod tile;
 mod time;
 mod utils;
-mod visibility;
-mod won;
 
-use std::{env::args, fs, io, path::PathBuf, time::Duration};
+use std::{env::args, path::PathBuf, process, time::Duration};
 
-use board::replay_board::get_time_modifier;
 use eframe::{
     egui::{self, Key},
     epaint::Vec2,
 };
-use replay_file::ReplayFile;
+use minesweeper_core::{
+    replay_file::{ReplayFile, ReplayFormat},
+    saved_game::SavedGame,
+};
 use sounds::SoundPlayer;
 
-use crate::board::{play_board::PlayBoard, replay_board::ReplayBoard};
+use crate::{
+    board::{play_board::PlayBoard, replay_board::ReplayBoard},
+    leaderboard::LeaderboardScreen,
+};
 
 fn get_sound_player() -> &'static mut SoundPlayer {
     static mut SOUND_PLAYER: Option<SoundPlayer> = None;
@@ -42,38 +49,8 @@ fn get_sound_player() -> &'static mut SoundPlayer {
     unsafe { SOUND_PLAYER.as_mut().unwrap() }
 }
 
-fn get_size() -> &'static mut (usize, usize) {
-    static mut SIZE: (usize, usize) = (8, 8);
-    unsafe { &mut SIZE }
-}
-
-fn set_size(size: (usize, usize), frame: &mut eframe::Frame) {
-    *get_size() = size;
-    frame.set_window_size(get_window_size());
-}
-
-fn get_mine_amount() -> &'static mut usize {
-    static mut MINE_AMOUNT: usize = 10;
-    unsafe { &mut MINE_AMOUNT }
-}
-
-fn get_in_replay_mode() -> &'static mut bool {
-    static mut IN_REPLAY_MODE: bool = false;
-    unsafe { &mut IN_REPLAY_MODE }
-}
-
-fn get_replay_file() -> &'static mut ReplayFile {
-    static mut REPLAY_FILE: Option<ReplayFile> = None;
-    if unsafe { REPLAY_FILE.is_none() } {
-        unsafe { REPLAY_FILE = Some(ReplayFile::default()) }
-    }
-
-    unsafe { REPLAY_FILE.as_mut().unwrap() }
-}
-
-fn get_allow_save_replay() -> &'static mut bool {
-    static mut ALLOW_SAVE_REPLAY: bool = false;
-    unsafe { &mut ALLOW_SAVE_REPLAY }
+fn default_replay_file() -> ReplayFile {
+    ReplayFile::from_string(include_str!("../assets/default_replay.json").to_owned()).unwrap()
 }
 
 const FIELD_SIZE: f32 = 32.0;
@@ -91,86 +68,193 @@ const INNER_CIRCLE_RADIUS: f32 = FIELD_SIZE * 0.235;
 
 const CLICK_ANIMATION_DURATION: Duration = Duration::from_millis(200);
 
-fn get_window_size() -> Vec2 {
+fn get_window_size(size: (usize, usize), in_replay_mode: bool) -> Vec2 {
     egui::vec2(
-        BORDER_WIDTH * 2.0 + get_size().0 as f32 * FIELD_SIZE,
+        BORDER_WIDTH * 2.0 + size.0 as f32 * FIELD_SIZE,
         BORDER_HEIGHT * 3.0
             + NUMBER_MARGIN * 2.0
             + NUMBER_HEIGHT
-            + get_size().1 as f32 * FIELD_SIZE
-            + if *get_in_replay_mode() { 43.0 } else { 0.0 },
+            + size.1 as f32 * FIELD_SIZE
+            + if in_replay_mode { 64.0 } else { 0.0 },
     )
 }
 
+fn replay_format_name(format: ReplayFormat) -> &'static str {
+    match format {
+        ReplayFormat::Json => "JSON (.json)",
+        ReplayFormat::Binary => "Binary (.msr)",
+        ReplayFormat::CompressedBinary => "Compressed binary (.msrz)",
+        ReplayFormat::Avf => "Minesweeper Arbiter (.avf)",
+        ReplayFormat::Rmv => "Viennasweeper (.rmv)",
+        ReplayFormat::Raw => "RAW (.rawvf)",
+    }
+}
+
 struct Minesweeper {
     play_board: PlayBoard,
     replay_board: ReplayBoard,
+    in_replay_mode: bool,
 
     choose_new_settings: bool,
     new_width_string: String,
     new_height_string: String,
     new_mines_string: String,
+    new_seed_string: String,
+
+    leaderboard: LeaderboardScreen,
+    show_leaderboard: bool,
+
+    // A game left unfinished last time, until the player picks whether to resume it.
+    saved_game: Option<SavedGame>,
 
     hovered_files: Vec<egui::HoveredFile>,
 }
 
 impl Minesweeper {
-    fn new(replay: Option<String>) -> Self {
+    fn new(replay: Option<String>, seed: Option<u64>) -> Self {
+        let mut replay_file = None;
         if let Some(replay) = replay {
             if let Ok(path) = PathBuf::try_from(replay) {
-                if !Minesweeper::load_replay_file(path) {
+                replay_file = Minesweeper::load_replay_file(path);
+                if replay_file.is_none() {
                     println!("Couldn't load replay file.");
                 }
             } else {
                 println!("Invalid file path.");
             }
         }
+        let in_replay_mode = replay_file.is_some();
 
         println!("Loading textures...");
-        let play_board = PlayBoard::new();
-        let replay_board = ReplayBoard::new();
+        let play_board = PlayBoard::new((8, 8), 10, seed);
+        let replay_board = ReplayBoard::new(replay_file.unwrap_or_else(default_replay_file));
         println!("Textures loaded!");
 
         Self {
             play_board,
             replay_board,
+            in_replay_mode,
 
             choose_new_settings: false,
             new_width_string: String::new(),
             new_height_string: String::new(),
             new_mines_string: String::new(),
+            new_seed_string: String::new(),
+
+            leaderboard: LeaderboardScreen::new(),
+            show_leaderboard: false,
+
+            saved_game: saved_game::load(),
 
             hovered_files: Vec::new(),
         }
     }
 
-    fn load_replay_file(path: PathBuf) -> bool {
-        if let io::Result::Ok(contents) = fs::read_to_string(&path) {
-            match ReplayFile::from_string(contents) {
-                Ok(replay) => {
-                    *get_replay_file() = replay;
-                    *get_size() = get_replay_file().size;
-                    *get_mine_amount() = get_replay_file().mines.len();
-                    *get_in_replay_mode() = true;
-                    true
-                }
-                Err(err) => {
-                    println!("Error loading replay file: {}", err);
-                    false
-                }
-            }
+    fn window_size(&self) -> Vec2 {
+        if self.in_replay_mode {
+            get_window_size(self.replay_board.size(), true)
         } else {
-            println!("Couldn't read file: {}", path.display());
-            false
+            get_window_size(self.play_board.size(), false)
+        }
+    }
+
+    fn load_replay_file(path: PathBuf) -> Option<ReplayFile> {
+        match ReplayFile::load(&path) {
+            Ok(replay) => Some(replay),
+            Err(err) => {
+                println!("Error loading replay file {}: {}", path.display(), err);
+                None
+            }
+        }
+    }
+
+    fn open_replay(&mut self, replay: ReplayFile, frame: &mut eframe::Frame) {
+        self.play_board.reset();
+        self.replay_board.set_replay(replay, false);
+        self.choose_new_settings = false;
+        self.show_leaderboard = false;
+        self.in_replay_mode = true;
+        frame.set_window_size(self.window_size());
+    }
+
+    fn leaderboard_update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        if ctx.input(|i| i.key_pressed(Key::Escape)) {
+            self.show_leaderboard = false;
+            return;
+        }
+
+        let mut open = None;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("High scores");
+
+            if ui.button("Back").clicked() {
+                self.show_leaderboard = false;
+            }
+
+            ui.separator();
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                open = self.leaderboard.draw(ui);
+            });
+        });
+
+        if let Some(replay) = open.and_then(Minesweeper::load_replay_file) {
+            self.open_replay(replay, frame);
         }
     }
 
+    fn resume_update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Resume unfinished game?");
+
+            if let Some(saved_game) = &self.saved_game {
+                ui.label(format!(
+                    "{}x{}, {} mines, {} clicks in {:.1}s",
+                    saved_game.size.0,
+                    saved_game.size.1,
+                    saved_game.mine_amount,
+                    saved_game.clicks.len(),
+                    saved_game.elapsed.as_secs_f32()
+                ));
+            }
+
+            ui.separator();
+
+            let resume = ui.button("Resume").clicked();
+            let discard = ui.button("Discard").clicked();
+            if !resume && !discard {
+                return;
+            }
+
+            if let Some(saved_game) = self.saved_game.take() {
+                if resume {
+                    self.play_board.resume(saved_game);
+                    frame.set_window_size(self.window_size());
+                }
+            }
+            if let Err(err) = saved_game::remove() {
+                println!("Couldn't remove saved game: {}", err);
+            }
+        });
+    }
+
     fn play_update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        if self.saved_game.is_some() {
+            self.resume_update(ctx, frame);
+            return;
+        }
+
+        if self.show_leaderboard {
+            self.leaderboard_update(ctx, frame);
+            return;
+        }
+
         if ctx.input(|i| i.key_pressed(Key::Escape)) {
             self.choose_new_settings = !self.choose_new_settings;
             self.new_width_string.clear();
             self.new_height_string.clear();
             self.new_mines_string.clear();
+            self.new_seed_string.clear();
         }
 
         egui::CentralPanel::default().show(ctx, |ui| {
@@ -178,23 +262,83 @@ impl Minesweeper {
                 ui.heading("New Settings");
 
                 if ui.button("Beginner").clicked() {
-                    set_size((8, 8), frame);
-                    *get_mine_amount() = 10;
-                    self.play_board.reset();
+                    self.play_board.change_settings((8, 8), 10);
+                    frame.set_window_size(get_window_size((8, 8), false));
                 }
 
                 if ui.button("Intermediate").clicked() {
-                    set_size((16, 16), frame);
-                    *get_mine_amount() = 40;
-                    self.play_board.reset();
+                    self.play_board.change_settings((16, 16), 40);
+                    frame.set_window_size(get_window_size((16, 16), false));
                 }
 
                 if ui.button("Expert").clicked() {
-                    set_size((30, 16), frame);
-                    *get_mine_amount() = 99;
-                    self.play_board.reset();
+                    self.play_board.change_settings((30, 16), 99);
+                    frame.set_window_size(get_window_size((30, 16), false));
+                }
+
+                let mut no_guess = self.play_board.no_guess();
+                if ui.checkbox(&mut no_guess, "No guessing").changed() {
+                    self.play_board.set_no_guess(no_guess);
+                }
+                if self.play_board.needs_guess() {
+                    ui.label("Couldn't find a board without guessing for this many mines, this one won't count as no guessing");
+                }
+
+                let mut practice = self.play_board.practice();
+                if ui
+                    .checkbox(&mut practice, "Practice mode (Ctrl+Z/Ctrl+Y to undo/redo)")
+                    .changed()
+                {
+                    self.play_board.set_practice(practice);
+                }
+
+                let mut question_marks = self.play_board.question_marks();
+                if ui
+                    .checkbox(&mut question_marks, "Question marks (right click a flag)")
+                    .changed()
+                {
+                    self.play_board.set_question_marks(question_marks);
                 }
 
+                let mut left_click_chords = self.play_board.left_click_chords();
+                if ui
+                    .checkbox(&mut left_click_chords, "Left click a number to chord")
+                    .changed()
+                {
+                    self.play_board.set_left_click_chords(left_click_chords);
+                }
+
+                let mut right_click_flags_around = self.play_board.right_click_flags_around();
+                if ui
+                    .checkbox(
+                        &mut right_click_flags_around,
+                        "Right click a number to flag around it",
+                    )
+                    .changed()
+                {
+                    self.play_board
+                        .set_right_click_flags_around(right_click_flags_around);
+                }
+
+                let mut replay_format = self.play_board.replay_format();
+                egui::ComboBox::from_label("Save replays as (S)")
+                    .selected_text(replay_format_name(replay_format))
+                    .show_ui(ui, |ui| {
+                        for format in [
+                            ReplayFormat::Json,
+                            ReplayFormat::Binary,
+                            ReplayFormat::CompressedBinary,
+                        ] {
+                            ui.selectable_value(
+                                &mut replay_format,
+                                format,
+                                replay_format_name(format),
+                            );
+                        }
+                    });
+                self.play_board.set_replay_format(replay_format);
+                self.replay_board.set_replay_format(replay_format);
+
                 ui.separator();
 
                 ui.horizontal(|ui| {
@@ -229,12 +373,58 @@ impl Minesweeper {
                         return;
                     }
 
-                    set_size((new_width, new_height), frame);
-                    *get_mine_amount() = new_mines.min((new_width * new_height).saturating_sub(9));
-                    self.play_board.reset();
+                    self.play_board.change_settings(
+                        (new_width, new_height),
+                        new_mines.min((new_width * new_height).saturating_sub(9)),
+                    );
+                    frame.set_window_size(get_window_size((new_width, new_height), false));
+                }
+
+                ui.separator();
+
+                ui.label(format!("Current seed: {}", self.play_board.seed()));
+
+                ui.horizontal(|ui| {
+                    ui.label("Seed:");
+                    ui.text_edit_singleline(&mut self.new_seed_string);
+                });
+
+                if ui.button("Set seed").clicked() {
+                    let Ok(new_seed) = self.new_seed_string.parse::<u64>() else {
+                        return;
+                    };
+
+                    self.play_board.set_seed(new_seed);
+                }
+
+                if self.play_board.seeded() && ui.button("Random seed").clicked() {
+                    self.play_board.clear_seed();
+                }
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.label("Player name:");
+                    let mut player_name = self.play_board.player_name().to_string();
+                    if ui.text_edit_singleline(&mut player_name).changed() {
+                        self.play_board.set_player_name(player_name);
+                    }
+                });
+
+                ui.separator();
+
+                if ui.button("High scores").clicked() {
+                    self.show_leaderboard = true;
                 }
             } else {
                 self.play_board.handle_inputs(ctx);
+                if let Some(replay) = self.play_board.take_won_replay() {
+                    let rank = self.leaderboard.record_win(
+                        &replay,
+                        self.play_board.no_guess() && !self.play_board.needs_guess(),
+                    );
+                    self.play_board.set_rank(rank);
+                }
                 self.play_board.draw(ui);
             }
         });
@@ -249,7 +439,7 @@ impl Minesweeper {
             if self.choose_new_settings {
                 ui.heading("Choose new replay");
                 ui.label(
-                    "Drag and drop a replay file anywhere in this app at any time to load it.",
+                    "Drag and drop a replay file (.json, .msr, .msrz, .avf or .rmv) anywhere in this app at any time to load it.",
                 );
                 ui.separator();
 
@@ -264,6 +454,11 @@ impl Minesweeper {
                         ui.label("Can't load multiple files at once.");
                     }
                 }
+
+                ui.separator();
+
+                ui.heading("Loaded replay");
+                self.replay_board.draw_metadata(ui);
             } else {
                 self.replay_board.handle_inputs(ctx);
                 self.replay_board.draw(ui);
@@ -274,6 +469,7 @@ impl Minesweeper {
 
 impl eframe::App for Minesweeper {
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        let mut dropped_replay = None;
         ctx.input(|i| {
             if !i.raw.hovered_files.is_empty() {
                 self.hovered_files = i.raw.hovered_files.clone();
@@ -282,58 +478,88 @@ impl eframe::App for Minesweeper {
                 self.hovered_files.clear();
                 if i.raw.dropped_files.len() == 1 {
                     if let Some(ref path) = i.raw.dropped_files[0].path {
-                        if Minesweeper::load_replay_file(path.clone()) {
-                            self.play_board.reset();
-                            self.replay_board.reset();
-                            get_time_modifier().reset();
-                            self.choose_new_settings = false;
-                            frame.set_window_size(get_window_size());
-                            *get_allow_save_replay() = false;
-                        }
+                        dropped_replay = Minesweeper::load_replay_file(path.clone());
                     }
                 }
             }
         });
+        if let Some(replay) = dropped_replay {
+            self.open_replay(replay, frame);
+        }
 
-        if !self.choose_new_settings && ctx.input(|i| i.key_pressed(Key::Enter)) {
-            if *get_in_replay_mode() {
-                *get_in_replay_mode() = false;
-                frame.set_window_size(get_window_size());
+        if !self.choose_new_settings
+            && !self.show_leaderboard
+            && ctx.input(|i| i.key_pressed(Key::Enter))
+        {
+            if self.in_replay_mode {
+                self.in_replay_mode = false;
             } else {
                 if let Some(replay) = self.play_board.create_replay() {
-                    *get_replay_file() = replay;
-                    *get_allow_save_replay() = true;
+                    self.replay_board.set_replay(replay, true);
                 } else {
-                    *get_replay_file() = ReplayFile::default();
-                    set_size(get_replay_file().size, frame);
-                    *get_mine_amount() = get_replay_file().mines.len();
-                    *get_allow_save_replay() = false;
+                    self.replay_board.set_replay(default_replay_file(), false);
                 }
 
-                *get_in_replay_mode() = true;
-                frame.set_window_size(get_window_size());
+                self.in_replay_mode = true;
             }
+            frame.set_window_size(self.window_size());
 
             self.play_board.reset();
             self.replay_board.reset();
-            get_time_modifier().reset();
         }
 
-        if *get_in_replay_mode() {
+        if self.in_replay_mode {
             self.replay_update(ctx, frame);
         } else {
             self.play_update(ctx, frame);
         }
     }
+
+    fn on_close_event(&mut self) -> bool {
+        // Still undecided about last time's game, so leave it for the next launch.
+        if self.saved_game.is_some() {
+            return true;
+        }
+
+        let result = match self.play_board.saved_game() {
+            Some(saved_game) => saved_game::save(&saved_game),
+            None => saved_game::remove(),
+        };
+        if let Err(err) = result {
+            println!("Couldn't save unfinished game: {}", err);
+        }
+
+        true
+    }
 }
 
 fn main() -> Result<(), eframe::Error> {
-    let minesweeper = Minesweeper::new(args().skip(1).next());
+    let mut replay = None;
+    let mut seed = None;
+
+    let args: Vec<String> = args().skip(1).collect();
+    if let Some(exit_code) = cli::run(&args) {
+        process::exit(exit_code);
+    }
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--seed" {
+            match args.next().map(|string| string.parse::<u64>()) {
+                Some(Ok(new_seed)) => seed = Some(new_seed),
+                _ => println!("Invalid seed."),
+            }
+        } else {
+            replay = Some(arg);
+        }
+    }
+
+    let minesweeper = Minesweeper::new(replay, seed);
 
     let options = eframe::NativeOptions {
         drag_and_drop_support: true,
         resizable: false,
-        initial_window_size: Some(get_window_size()),
+        initial_window_size: Some(minesweeper.window_size()),
         ..Default::default()
     };
 
@@ -0,0 +1,32 @@
+use std::time::Duration;
+
+use eframe::epaint::Pos2;
+use minesweeper_core::mouse_event::{MouseAction, MouseEvent};
+
+use crate::{BORDER_HEIGHT, BORDER_WIDTH, FIELD_SIZE, NUMBER_HEIGHT, NUMBER_MARGIN};
+
+const BOARD_TOP: f32 = BORDER_HEIGHT * 2.0 + NUMBER_MARGIN * 2.0 + NUMBER_HEIGHT;
+
+pub(crate) trait ScreenMouseEvent {
+    fn coordinates(&self) -> (f32, f32);
+
+    fn from_mouse(action: MouseAction, pos: Pos2, duration: Duration) -> Self;
+}
+
+impl ScreenMouseEvent for MouseEvent {
+    fn coordinates(&self) -> (f32, f32) {
+        (
+            BORDER_WIDTH + self.x * FIELD_SIZE,
+            BOARD_TOP + self.y * FIELD_SIZE,
+        )
+    }
+
+    fn from_mouse(action: MouseAction, pos: Pos2, duration: Duration) -> Self {
+        Self::new(
+            action,
+            (pos.x - BORDER_WIDTH) / FIELD_SIZE,
+            (pos.y - BOARD_TOP) / FIELD_SIZE,
+            duration,
+        )
+    }
+}
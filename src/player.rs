@@ -0,0 +1,22 @@
+use std::{env, fs, path::PathBuf};
+
+use crate::leaderboard::data_dir;
+
+fn name_path() -> PathBuf {
+    data_dir().join("player_name")
+}
+
+pub(crate) fn load_name() -> String {
+    fs::read_to_string(name_path())
+        .ok()
+        .or_else(|| env::var("USER").ok())
+        .or_else(|| env::var("USERNAME").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "Player".to_string())
+}
+
+pub(crate) fn save_name(name: &str) -> Result<(), String> {
+    fs::create_dir_all(data_dir()).map_err(|e| e.to_string())?;
+    fs::write(name_path(), name).map_err(|e| e.to_string())
+}
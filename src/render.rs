@@ -0,0 +1,276 @@
+pub(crate) mod animation;
+pub(crate) mod canvas;
+pub(crate) mod svg;
+
+use std::{fs, path::Path, time::Duration};
+
+use eframe::epaint::Color32;
+use minesweeper_core::{
+    field_generator::replay_field_generator::ReplayFieldGenerator, fields::Fields,
+    replay_file::ReplayFile, tile::Tile, visibility::Visibility, won::Won,
+};
+
+use self::{
+    canvas::{Canvas, Sprites},
+    svg::SvgImage,
+};
+use crate::{
+    border::{CORNER_SVGS, HORIZONTAL, VERTICAL},
+    textures::{FIELD_SVGS, NUMBER_BACKGROUND, NUMBER_SVGS},
+    BORDER_HEIGHT, BORDER_WIDTH, FIELD_SIZE, NUMBER_HEIGHT, NUMBER_MARGIN, NUMBER_WIDTH,
+};
+
+const BACKGROUND: Color32 = Color32::from_rgb(192, 192, 192);
+
+const FIELD_COUNT: usize = FIELD_SVGS.len();
+const DIGIT_COUNT: usize = NUMBER_SVGS.len();
+const CORNER_COUNT: usize = CORNER_SVGS.len();
+
+// One of the asset SVGs, indexed like the textures in `Textures` and `Border`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Sprite {
+    Field(usize),
+    Digit(usize),
+    CounterBackground,
+    Corner(usize),
+    Horizontal,
+    Vertical,
+}
+
+impl Sprite {
+    fn svg(self) -> &'static str {
+        match self {
+            Sprite::Field(index) => FIELD_SVGS[index],
+            Sprite::Digit(index) => NUMBER_SVGS[index],
+            Sprite::CounterBackground => NUMBER_BACKGROUND,
+            Sprite::Corner(index) => CORNER_SVGS[index],
+            Sprite::Horizontal => HORIZONTAL,
+            Sprite::Vertical => VERTICAL,
+        }
+    }
+
+    // The size the game rasterizes it at.
+    fn size(self) -> (u32, u32) {
+        match self {
+            Sprite::Field(_) => (FIELD_SIZE as u32, FIELD_SIZE as u32),
+            Sprite::Digit(_) => (NUMBER_WIDTH as u32, NUMBER_HEIGHT as u32),
+            Sprite::CounterBackground => ((FIELD_SIZE * 2.46) as u32, (FIELD_SIZE * 1.5) as u32),
+            Sprite::Corner(_) => (BORDER_WIDTH as u32, BORDER_HEIGHT as u32),
+            Sprite::Horizontal => (1, BORDER_HEIGHT as u32),
+            Sprite::Vertical => (BORDER_WIDTH as u32, 1),
+        }
+    }
+
+    fn name(self) -> String {
+        match self {
+            Sprite::Field(index) => format!("field{}", index),
+            Sprite::Digit(index) => format!("digit{}", index),
+            Sprite::CounterBackground => "counter_background".to_string(),
+            Sprite::Corner(index) => format!("corner{}", index),
+            Sprite::Horizontal => "horizontal".to_string(),
+            Sprite::Vertical => "vertical".to_string(),
+        }
+    }
+}
+
+pub(crate) trait Surface {
+    fn paint(&mut self, sprite: Sprite, x: f32, y: f32, width: f32, height: f32);
+}
+
+pub(crate) fn canvas_size(size: (usize, usize)) -> [usize; 2] {
+    let window_size = crate::get_window_size(size, false);
+    [
+        window_size.x.round() as usize,
+        window_size.y.round() as usize,
+    ]
+}
+
+fn field_sprite(fields: &Fields, x: usize, y: usize, reveal_mines: bool) -> Sprite {
+    let field = &fields.get_fields()[y * fields.get_size().0 + x];
+
+    Sprite::Field(match fields.won {
+        Won::Lost(pos) if pos.x == x && pos.y == y => 4,
+        Won::Lost(_) if !field.is_mine() && field.is_flagged() => 5,
+        _ => match (field.get_tile(), field.get_visibility()) {
+            (Tile::Mine, Visibility::Closed | Visibility::Questioned) if reveal_mines => 2,
+            (_, Visibility::Closed) => 0,
+            (_, Visibility::Flagged) => 3,
+            (_, Visibility::Questioned) => 14,
+
+            (Tile::Empty, Visibility::Open) => 1,
+            (Tile::Mine, Visibility::Open) => 2,
+            (Tile::Number(num), Visibility::Open) => 5 + usize::from(num),
+        },
+    })
+}
+
+fn draw_number(surface: &mut impl Surface, number: i32, x: f32) {
+    surface.paint(
+        Sprite::CounterBackground,
+        x,
+        BORDER_HEIGHT,
+        FIELD_SIZE * 2.46,
+        FIELD_SIZE * 1.5,
+    );
+
+    let mut num = number.clamp(-99, 999);
+    let mut sign = num.signum();
+    num = num.abs();
+
+    for i in (0..3).rev() {
+        let digit = if num == 0 && sign == -1 {
+            sign = 0;
+            10
+        } else {
+            (num % 10) as usize
+        };
+        surface.paint(
+            Sprite::Digit(digit),
+            x + (i as f32 * (NUMBER_WIDTH + NUMBER_MARGIN)) + NUMBER_MARGIN,
+            BORDER_HEIGHT + NUMBER_MARGIN,
+            NUMBER_WIDTH,
+            NUMBER_HEIGHT,
+        );
+
+        num /= 10;
+    }
+}
+
+fn draw_border(surface: &mut impl Surface, size: (usize, usize)) {
+    let width = BORDER_WIDTH * 2.0 + FIELD_SIZE * size.0 as f32;
+    let height = canvas_size(size)[1] as f32;
+    let right = BORDER_WIDTH + FIELD_SIZE * size.0 as f32;
+    let middle = BORDER_HEIGHT + NUMBER_MARGIN * 2.0 + NUMBER_HEIGHT;
+    let bottom =
+        BORDER_HEIGHT * 2.0 + NUMBER_MARGIN * 2.0 + NUMBER_HEIGHT + FIELD_SIZE * size.1 as f32;
+
+    for y in [0.0, middle, bottom] {
+        surface.paint(Sprite::Horizontal, 0.0, y, width, BORDER_HEIGHT);
+    }
+    for x in [0.0, right] {
+        surface.paint(Sprite::Vertical, x, 0.0, BORDER_WIDTH, height);
+    }
+
+    for (corner, (x, y)) in [
+        (0.0, 0.0),
+        (right, 0.0),
+        (0.0, bottom),
+        (right, bottom),
+        (0.0, middle),
+        (right, middle),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        surface.paint(Sprite::Corner(corner), x, y, BORDER_WIDTH, BORDER_HEIGHT);
+    }
+}
+
+// Draws what `Board::draw` draws for the given state, `seconds` being the timer.
+pub(crate) fn draw_board(
+    surface: &mut impl Surface,
+    size: (usize, usize),
+    mine_amount: usize,
+    fields: Option<&Fields>,
+    seconds: u64,
+    reveal_mines: bool,
+) {
+    for x in 0..size.0 {
+        for y in 0..size.1 {
+            surface.paint(
+                fields.map_or(Sprite::Field(0), |fields| {
+                    field_sprite(fields, x, y, reveal_mines)
+                }),
+                BORDER_WIDTH + x as f32 * FIELD_SIZE,
+                BORDER_HEIGHT * 2.0 + NUMBER_MARGIN * 2.0 + NUMBER_HEIGHT + y as f32 * FIELD_SIZE,
+                FIELD_SIZE,
+                FIELD_SIZE,
+            );
+        }
+    }
+    draw_border(surface, size);
+
+    draw_number(
+        surface,
+        fields.map_or(mine_amount as i32, |fields| fields.mines_left),
+        BORDER_WIDTH - 1.0,
+    );
+    draw_number(
+        surface,
+        seconds as i32,
+        BORDER_WIDTH + FIELD_SIZE * size.0 as f32 - Sprite::CounterBackground.size().0 as f32,
+    );
+}
+
+// Renders a board to a PNG, or to an SVG if the path ends in `.svg`.
+pub(crate) fn render_image(
+    size: (usize, usize),
+    mine_amount: usize,
+    fields: Option<&Fields>,
+    duration: Duration,
+    reveal_mines: bool,
+    path: &Path,
+) -> Result<(), String> {
+    let svg = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("svg"));
+
+    if svg {
+        let mut image = SvgImage::new(canvas_size(size));
+        draw_board(
+            &mut image,
+            size,
+            mine_amount,
+            fields,
+            duration.as_secs(),
+            reveal_mines,
+        );
+        fs::write(path, image.finish()).map_err(|err| err.to_string())
+    } else {
+        let sprites = Sprites::new();
+        let mut canvas = Canvas::new(&sprites, canvas_size(size));
+        draw_board(
+            &mut canvas,
+            size,
+            mine_amount,
+            fields,
+            duration.as_secs(),
+            reveal_mines,
+        );
+        canvas.save_png(path)
+    }
+}
+
+// Renders the board of a replay right after its first `click` clicks.
+pub(crate) fn render_replay_image(
+    replay_file: &ReplayFile,
+    click: usize,
+    reveal_mines: bool,
+    path: &Path,
+) -> Result<(), String> {
+    let click = click.min(replay_file.clicks.len());
+    let mut fields = replay_file.fields_at(click);
+    // Before the first click there's no board yet, but the replay knows where the mines went.
+    if fields.is_none() && reveal_mines {
+        fields = replay_file.clicks.first().map(|first| {
+            Fields::new(
+                &ReplayFieldGenerator::new(replay_file.mines.clone()),
+                replay_file.size,
+                replay_file.mines.len(),
+                &first.position(),
+            )
+        });
+    }
+
+    render_image(
+        replay_file.size,
+        replay_file.mines.len(),
+        fields.as_ref(),
+        match click {
+            0 => Duration::ZERO,
+            click => replay_file.clicks[click - 1].duration(),
+        },
+        reveal_mines,
+        path,
+    )
+}
@@ -0,0 +1,157 @@
+use std::{fs, fs::File, io::BufWriter, path::Path, time::Duration};
+
+use eframe::epaint::Color32;
+use minesweeper_core::replay_file::ReplayFile;
+
+use super::{
+    canvas::{Canvas, Sprites},
+    canvas_size, draw_board,
+};
+use crate::{
+    cursor, utils::lerp, CLICK_ANIMATION_DURATION, INNER_CIRCLE_RADIUS, OUTER_CIRCLE_RADIUS,
+};
+
+// GIF frame delays are in hundredths of a second, anything faster gets slowed down by viewers.
+pub(crate) const MAX_GIF_FPS: u32 = 50;
+
+// How long the last frame of a GIF stays up before it loops.
+const GIF_END_DELAY: u16 = 200;
+
+// Quantizing takes most of the time, and the sprites don't have many colours to lose anyway.
+const GIF_QUANTIZE_SPEED: i32 = 30;
+
+fn draw_cursor(canvas: &mut Canvas, position: (f32, f32), radius: f32) {
+    canvas.circle(
+        position,
+        radius,
+        Color32::from_rgba_premultiplied(125, 125, 125, 77),
+    );
+    canvas.circle(
+        position,
+        INNER_CIRCLE_RADIUS,
+        Color32::from_rgba_premultiplied(128, 128, 128, 159),
+    );
+}
+
+// Plays the replay back at `fps`, calling `frame` with every frame and how long it's shown.
+fn render_frames(
+    replay_file: &ReplayFile,
+    fps: u32,
+    mut frame: impl FnMut(&Canvas, Duration) -> Result<(), String>,
+) -> Result<usize, String> {
+    let sprites = Sprites::new();
+    let frame_count = (replay_file.duration.as_secs_f64() * fps as f64).ceil() as usize + 1;
+
+    let mut fields = None;
+    let mut next_click = 0;
+    let mut last_click = None;
+
+    for i in 0..frame_count {
+        let time = Duration::from_secs_f64(i as f64 / fps as f64).min(replay_file.duration);
+        while let Some(click) = replay_file
+            .clicks
+            .get(next_click)
+            .filter(|click| click.duration() <= time)
+        {
+            replay_file.apply_click(&mut fields, click);
+            last_click = Some(click.duration());
+            next_click += 1;
+        }
+
+        let mut canvas = Canvas::new(&sprites, canvas_size(replay_file.size));
+        draw_board(
+            &mut canvas,
+            replay_file.size,
+            replay_file.mines.len(),
+            fields.as_ref(),
+            time.as_secs(),
+            false,
+        );
+
+        let (position, pressed) = cursor::position(replay_file, next_click, time);
+        let since_click = last_click.map(|last_click| time - last_click);
+        let radius = match since_click {
+            _ if pressed => INNER_CIRCLE_RADIUS,
+            Some(elapsed) if elapsed < CLICK_ANIMATION_DURATION => lerp(
+                INNER_CIRCLE_RADIUS,
+                OUTER_CIRCLE_RADIUS,
+                elapsed.as_secs_f32() / CLICK_ANIMATION_DURATION.as_secs_f32(),
+            ),
+            _ => OUTER_CIRCLE_RADIUS,
+        };
+        draw_cursor(&mut canvas, position, radius);
+
+        frame(
+            &canvas,
+            Duration::from_secs_f64((i + 1) as f64 / fps as f64)
+                - Duration::from_secs_f64(i as f64 / fps as f64),
+        )?;
+    }
+
+    Ok(frame_count)
+}
+
+// Writes `frame_00000.png`, `frame_00001.png`, ... into the directory.
+pub(crate) fn render_png_frames(
+    replay_file: &ReplayFile,
+    fps: u32,
+    directory: &Path,
+) -> Result<usize, String> {
+    fs::create_dir_all(directory).map_err(|err| err.to_string())?;
+
+    let mut index = 0;
+    render_frames(replay_file, fps, |canvas, _| {
+        canvas.save_png(&directory.join(format!("frame_{:05}.png", index)))?;
+        index += 1;
+        Ok(())
+    })
+}
+pub(crate) fn render_gif(replay_file: &ReplayFile, fps: u32, path: &Path) -> Result<usize, String> {
+    let [width, height] = canvas_size(replay_file.size);
+    let file = File::create(path).map_err(|err| err.to_string())?;
+    let mut encoder = gif::Encoder::new(BufWriter::new(file), width as u16, height as u16, &[])
+        .map_err(|err| err.to_string())?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(|err| err.to_string())?;
+
+    // Delays are rounded against the total so they don't drift, and frames that didn't change
+    // are merged into the one before them.
+    let mut elapsed = Duration::ZERO;
+    let mut written = 0;
+    let mut pending: Option<(Vec<u8>, u64)> = None;
+    let mut write = |pixels: &mut Vec<u8>, delay: u64| {
+        let mut frame =
+            gif::Frame::from_rgba_speed(width as u16, height as u16, pixels, GIF_QUANTIZE_SPEED);
+        frame.delay = delay.min(u16::MAX as u64) as u16;
+        encoder.write_frame(&frame).map_err(|err| err.to_string())
+    };
+
+    render_frames(replay_file, fps, |canvas, duration| {
+        let start = (elapsed.as_millis() as u64 + 5) / 10;
+        elapsed += duration;
+        let delay = (elapsed.as_millis() as u64 + 5) / 10 - start;
+
+        let pixels = canvas.rgba();
+        match &mut pending {
+            Some((pending_pixels, pending_delay)) if *pending_pixels == pixels => {
+                *pending_delay += delay;
+            }
+            _ => {
+                if let Some((mut pixels, delay)) = pending.replace((pixels, delay)) {
+                    write(&mut pixels, delay)?;
+                    written += 1;
+                }
+            }
+        }
+
+        Ok(())
+    })?;
+
+    if let Some((mut pixels, delay)) = pending {
+        write(&mut pixels, delay + GIF_END_DELAY as u64)?;
+        written += 1;
+    }
+
+    Ok(written)
+}
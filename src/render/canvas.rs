@@ -0,0 +1,158 @@
+use std::{fs::File, io::BufWriter, path::Path};
+
+use eframe::epaint::{Color32, ColorImage};
+use egui_extras::image::{load_svg_bytes_with_size, FitTo};
+
+use super::{Sprite, Surface, BACKGROUND, CORNER_COUNT, DIGIT_COUNT, FIELD_COUNT};
+
+// The sprites rasterized at the sizes the game rasterizes them at, so renders look like the window.
+pub(crate) struct Sprites {
+    fields: Vec<ColorImage>,
+    digits: Vec<ColorImage>,
+    counter_background: ColorImage,
+    corners: Vec<ColorImage>,
+    horizontal: ColorImage,
+    vertical: ColorImage,
+}
+
+fn rasterize(sprite: Sprite) -> ColorImage {
+    let (width, height) = sprite.size();
+    load_svg_bytes_with_size(sprite.svg().as_bytes(), FitTo::Size(width, height)).unwrap()
+}
+
+impl Sprites {
+    pub(crate) fn new() -> Self {
+        Self {
+            fields: (0..FIELD_COUNT)
+                .map(|index| rasterize(Sprite::Field(index)))
+                .collect(),
+            digits: (0..DIGIT_COUNT)
+                .map(|index| rasterize(Sprite::Digit(index)))
+                .collect(),
+            counter_background: rasterize(Sprite::CounterBackground),
+            corners: (0..CORNER_COUNT)
+                .map(|index| rasterize(Sprite::Corner(index)))
+                .collect(),
+            horizontal: rasterize(Sprite::Horizontal),
+            vertical: rasterize(Sprite::Vertical),
+        }
+    }
+
+    fn get(&self, sprite: Sprite) -> &ColorImage {
+        match sprite {
+            Sprite::Field(index) => &self.fields[index],
+            Sprite::Digit(index) => &self.digits[index],
+            Sprite::CounterBackground => &self.counter_background,
+            Sprite::Corner(index) => &self.corners[index],
+            Sprite::Horizontal => &self.horizontal,
+            Sprite::Vertical => &self.vertical,
+        }
+    }
+}
+
+// A software canvas with premultiplied pixels, blended the same way egui blends.
+pub(crate) struct Canvas<'a> {
+    sprites: &'a Sprites,
+    size: [usize; 2],
+    pixels: Vec<Color32>,
+}
+
+impl<'a> Canvas<'a> {
+    pub(crate) fn new(sprites: &'a Sprites, size: [usize; 2]) -> Self {
+        Self {
+            sprites,
+            size,
+            pixels: vec![BACKGROUND; size[0] * size[1]],
+        }
+    }
+
+    pub(crate) fn size(&self) -> [usize; 2] {
+        self.size
+    }
+
+    fn blend(&mut self, x: usize, y: usize, color: Color32) {
+        let pixel = &mut self.pixels[y * self.size[0] + x];
+        let inverse = 255 - color.a() as u32;
+        let mix =
+            |src: u8, dst: u8| (src as u32 + (dst as u32 * inverse + 127) / 255).min(255) as u8;
+
+        *pixel = Color32::from_rgba_premultiplied(
+            mix(color.r(), pixel.r()),
+            mix(color.g(), pixel.g()),
+            mix(color.b(), pixel.b()),
+            mix(color.a(), pixel.a()),
+        );
+    }
+
+    pub(crate) fn circle(&mut self, center: (f32, f32), radius: f32, color: Color32) {
+        let top = (center.1 - radius - 1.0).floor().max(0.0) as usize;
+        let bottom = ((center.1 + radius + 1.0).ceil().max(0.0) as usize).min(self.size[1]);
+        let left = (center.0 - radius - 1.0).floor().max(0.0) as usize;
+        let right = ((center.0 + radius + 1.0).ceil().max(0.0) as usize).min(self.size[0]);
+
+        for y in top..bottom {
+            for x in left..right {
+                let distance = (x as f32 + 0.5 - center.0).hypot(y as f32 + 0.5 - center.1);
+                let coverage = (radius + 0.5 - distance).clamp(0.0, 1.0);
+                if coverage > 0.0 {
+                    let scale = |channel: u8| (channel as f32 * coverage).round() as u8;
+                    self.blend(
+                        x,
+                        y,
+                        Color32::from_rgba_premultiplied(
+                            scale(color.r()),
+                            scale(color.g()),
+                            scale(color.b()),
+                            scale(color.a()),
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    pub(crate) fn rgba(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|pixel| pixel.to_srgba_unmultiplied())
+            .collect()
+    }
+
+    pub(crate) fn save_png(&self, path: &Path) -> Result<(), String> {
+        let file = File::create(path).map_err(|err| err.to_string())?;
+        let mut encoder = png::Encoder::new(
+            BufWriter::new(file),
+            self.size[0] as u32,
+            self.size[1] as u32,
+        );
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.rgba()))
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Surface for Canvas<'_> {
+    // Stretches the sprite over the rect, snapped to whole pixels so neighbouring tiles don't gap.
+    fn paint(&mut self, sprite: Sprite, x: f32, y: f32, width: f32, height: f32) {
+        let sprites = self.sprites;
+        let image = sprites.get(sprite);
+        let (left, top) = (x.round() as isize, y.round() as isize);
+        let (right, bottom) = ((x + width).round() as isize, (y + height).round() as isize);
+        if right <= left || bottom <= top {
+            return;
+        }
+
+        for py in top.max(0)..bottom.min(self.size[1] as isize) {
+            let sy = (py - top) as usize * image.size[1] / (bottom - top) as usize;
+            for px in left.max(0)..right.min(self.size[0] as isize) {
+                let sx = (px - left) as usize * image.size[0] / (right - left) as usize;
+                let color = image.pixels[sy * image.size[0] + sx];
+                self.blend(px as usize, py as usize, color);
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use std::fmt::Write;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use super::{Sprite, Surface, BACKGROUND};
+
+// An SVG document that places the asset SVGs as images, defined once each and reused. They're
+// embedded instead of inlined because their styles and ids would clash with each other.
+pub(crate) struct SvgImage {
+    size: [usize; 2],
+    defined: Vec<Sprite>,
+    body: String,
+}
+
+impl SvgImage {
+    pub(crate) fn new(size: [usize; 2]) -> Self {
+        Self {
+            size,
+            defined: Vec::new(),
+            body: String::new(),
+        }
+    }
+
+    pub(crate) fn finish(self) -> String {
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="{0}" height="{1}" viewBox="0 0 {0} {1}">
+<defs>
+"#,
+            self.size[0], self.size[1]
+        );
+        for sprite in self.defined {
+            let (width, height) = sprite.size();
+            writeln!(
+                svg,
+                r#"<image id="{}" width="{}" height="{}" preserveAspectRatio="none" xlink:href="data:image/svg+xml;base64,{}"/>"#,
+                sprite.name(),
+                width,
+                height,
+                STANDARD.encode(sprite.svg())
+            )
+            .unwrap();
+        }
+        writeln!(
+            svg,
+            r##"</defs>
+<rect width="100%" height="100%" fill="#{:02x}{:02x}{:02x}"/>"##,
+            BACKGROUND.r(),
+            BACKGROUND.g(),
+            BACKGROUND.b()
+        )
+        .unwrap();
+        svg.push_str(&self.body);
+        svg.push_str("</svg>\n");
+
+        svg
+    }
+}
+
+impl Surface for SvgImage {
+    fn paint(&mut self, sprite: Sprite, x: f32, y: f32, width: f32, height: f32) {
+        if !self.defined.contains(&sprite) {
+            self.defined.push(sprite);
+        }
+
+        let (sprite_width, sprite_height) = sprite.size();
+        writeln!(
+            self.body,
+            r##"<use xlink:href="#{}" transform="translate({} {}) scale({} {})"/>"##,
+            sprite.name(),
+            x,
+            y,
+            width / sprite_width as f32,
+            height / sprite_height as f32
+        )
+        .unwrap();
+    }
+}
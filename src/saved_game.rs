@@ -0,0 +1,30 @@
+use std::{fs, io, path::PathBuf};
+
+use minesweeper_core::saved_game::SavedGame;
+
+use crate::leaderboard::data_dir;
+
+fn saved_game_path() -> PathBuf {
+    data_dir().join("saved_game.json")
+}
+
+pub(crate) fn load() -> Option<SavedGame> {
+    match SavedGame::load(&saved_game_path()) {
+        Ok(saved_game) => saved_game,
+        Err(err) => {
+            println!("Couldn't load saved game: {}", err);
+            None
+        }
+    }
+}
+
+pub(crate) fn save(saved_game: &SavedGame) -> Result<(), String> {
+    saved_game.save(&saved_game_path())
+}
+
+pub(crate) fn remove() -> Result<(), String> {
+    match fs::remove_file(saved_game_path()) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.to_string()),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,38 @@
+use std::{fs, io, path::PathBuf};
+
+use minesweeper_core::replay_file::signature::{public_key, PUBLIC_KEY_LENGTH};
+
+use crate::leaderboard::data_dir;
+
+fn key_path() -> PathBuf {
+    data_dir().join("signing_key")
+}
+
+// Every install gets its own key, created the first time a game is played.
+pub(crate) fn load_or_create_key() -> Result<[u8; 32], String> {
+    let path = key_path();
+
+    match fs::read(&path) {
+        Ok(bytes) => bytes
+            .try_into()
+            .map_err(|_| format!("{} is corrupted", path.display())),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            let key: [u8; 32] = rand::random();
+            fs::create_dir_all(data_dir()).map_err(|e| e.to_string())?;
+            fs::write(&path, key).map_err(|e| e.to_string())?;
+            Ok(key)
+        }
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+// Replays only count as signed when they were signed on this install.
+pub(crate) fn trusted_keys() -> Vec<[u8; PUBLIC_KEY_LENGTH]> {
+    match load_or_create_key() {
+        Ok(key) => vec![public_key(&key)],
+        Err(err) => {
+            println!("Couldn't load signing key: {}", err);
+            Vec::new()
+        }
+    }
+}
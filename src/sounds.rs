@@ -2,6 +2,7 @@ use soloud::{AudioExt, LoadExt, Soloud, Wav};
 
 const FLAG_SOUND: &[u8] = include_bytes!("../assets/flag.wav");
 const UNFLAG_SOUND: &[u8] = include_bytes!("../assets/unflag.wav");
+const QUESTION_SOUND: &[u8] = include_bytes!("../assets/question.wav");
 
 const OPEN_SOUND: &[u8] = include_bytes!("../assets/open.wav");
 const OPEN_FROM_FLAGS_SOUND: &[u8] = include_bytes!("../assets/open_from_flags.wav");
@@ -12,6 +13,7 @@ const WIN_SOUND: &[u8] = include_bytes!("../assets/win.wav");
 pub(crate) enum Sounds {
     Flag,
     Unflag,
+    Question,
 
     Open,
     OpenFromFlags,
@@ -23,6 +25,7 @@ pub(crate) enum Sounds {
 pub(crate) struct SoundPlayer {
     flag: Wav,
     unflag: Wav,
+    question: Wav,
 
     open: Wav,
     open_from_flags: Wav,
@@ -35,6 +38,7 @@ impl SoundPlayer {
     pub(crate) fn new() -> Self {
         let mut flag = Wav::default();
         let mut unflag = Wav::default();
+        let mut question = Wav::default();
 
         let mut open = Wav::default();
         let mut open_from_flags = Wav::default();
@@ -44,6 +48,7 @@ impl SoundPlayer {
 
         flag.load_mem(FLAG_SOUND).unwrap();
         unflag.load_mem(UNFLAG_SOUND).unwrap();
+        question.load_mem(QUESTION_SOUND).unwrap();
 
         open.load_mem(OPEN_SOUND).unwrap();
         open_from_flags.load_mem(OPEN_FROM_FLAGS_SOUND).unwrap();
@@ -54,6 +59,7 @@ impl SoundPlayer {
         Self {
             flag,
             unflag,
+            question,
 
             open,
             open_from_flags,
@@ -75,6 +81,7 @@ impl SoundPlayer {
         unsafe { SOLOUD.as_ref().unwrap() }.play(match sound {
             Sounds::Flag => &self.flag,
             Sounds::Unflag => &self.unflag,
+            Sounds::Question => &self.question,
 
             Sounds::Open => &self.open,
             Sounds::OpenFromFlags => &self.open_from_flags,
@@ -0,0 +1,74 @@
+use eframe::egui::{self, Grid};
+use minesweeper_core::stats::Stats;
+
+pub(crate) fn draw_stats_window(stats: &Stats, rank: Option<usize>, ctx: &egui::Context) {
+    egui::Window::new("Statistics")
+        .resizable(false)
+        .show(ctx, |ui| {
+            Grid::new("stats").striped(true).show(ui, |ui| {
+                ui.label("Result");
+                ui.label(if stats.won { "Won" } else { "Lost" });
+                ui.end_row();
+
+                ui.label("Time");
+                ui.label(format!("{:.3}", stats.duration.as_secs_f32()));
+                ui.end_row();
+
+                if let Some(rank) = rank {
+                    ui.label("High score");
+                    ui.label(format!("#{}", rank + 1));
+                    ui.end_row();
+                }
+
+                ui.label("3BV");
+                ui.label(format!("{}/{}", stats.solved_bbbv, stats.bbbv));
+                ui.end_row();
+
+                ui.label("3BV/s");
+                ui.label(format!("{:.3}", stats.bbbv_per_second()));
+                ui.end_row();
+
+                ui.label("Clicks");
+                ui.label(format!(
+                    "{} ({} effective, {} wasted)",
+                    stats.clicks(),
+                    stats.effective_clicks(),
+                    stats.wasted_clicks()
+                ));
+                ui.end_row();
+
+                for (name, click_stats) in [
+                    ("Left", stats.left),
+                    ("Right", stats.right),
+                    ("Chord", stats.chord),
+                ] {
+                    ui.label(name);
+                    ui.label(format!(
+                        "{} ({} effective, {} wasted)",
+                        click_stats.total,
+                        click_stats.effective,
+                        click_stats.wasted()
+                    ));
+                    ui.end_row();
+                }
+
+                if stats.questions > 0 {
+                    ui.label("Question marks");
+                    ui.label(stats.questions.to_string());
+                    ui.end_row();
+                }
+
+                ui.label("IOE");
+                ui.label(format!("{:.3}", stats.ioe()));
+                ui.end_row();
+
+                ui.label("Throughput");
+                ui.label(format!("{:.3}", stats.throughput()));
+                ui.end_row();
+
+                ui.label("Correctness");
+                ui.label(format!("{:.3}", stats.correctness()));
+                ui.end_row();
+            });
+        });
+}
@@ -7,6 +7,7 @@ const EMPTY: &str = include_str!("../assets/empty.svg");
 
 const MINE: &str = include_str!("../assets/mine.svg");
 const FLAG: &str = include_str!("../assets/flag.svg");
+const QUESTION: &str = include_str!("../assets/question.svg");
 
 const MINE_RED: &str = include_str!("../assets/mine_red.svg");
 const MINE_WRONG: &str = include_str!("../assets/mine_wrong.svg");
@@ -32,7 +33,25 @@ const NUMBER_EIGHT: &str = include_str!("../assets/7segment8.svg");
 const NUMBER_NINE: &str = include_str!("../assets/7segment9.svg");
 const NUMBER_MINUS: &str = include_str!("../assets/7segment-.svg");
 
-const NUMBER_BACKGROUND: &str = include_str!("../assets/nums_background.svg");
+pub(crate) const NUMBER_BACKGROUND: &str = include_str!("../assets/nums_background.svg");
+
+pub(crate) const FIELD_SVGS: [&str; 15] = [
+    CLOSED, EMPTY, MINE, FLAG, MINE_RED, MINE_WRONG, ONE, TWO, THREE, FOUR, FIVE, SIX, SEVEN,
+    EIGHT, QUESTION,
+];
+pub(crate) const NUMBER_SVGS: [&str; 11] = [
+    NUMBER_ZERO,
+    NUMBER_ONE,
+    NUMBER_TWO,
+    NUMBER_THREE,
+    NUMBER_FOUR,
+    NUMBER_FIVE,
+    NUMBER_SIX,
+    NUMBER_SEVEN,
+    NUMBER_EIGHT,
+    NUMBER_NINE,
+    NUMBER_MINUS,
+];
 
 pub(super) struct Textures {
     pub(super) field_textures: Vec<RetainedImage>,
@@ -43,44 +62,29 @@ pub(super) struct Textures {
 impl Textures {
     pub(super) fn new() -> Self {
         Self {
-            field_textures: [
-                CLOSED, EMPTY, MINE, FLAG, MINE_RED, MINE_WRONG, ONE, TWO, THREE, FOUR, FIVE, SIX,
-                SEVEN, EIGHT,
-            ]
-            .iter()
-            .map(|svg| {
-                RetainedImage::from_svg_bytes_with_size(
-                    "I have no debug name unfortunately",
-                    svg.as_bytes(),
-                    FitTo::Size(FIELD_SIZE as u32, FIELD_SIZE as u32),
-                )
-                .unwrap()
-            })
-            .collect(),
+            field_textures: FIELD_SVGS
+                .iter()
+                .map(|svg| {
+                    RetainedImage::from_svg_bytes_with_size(
+                        "I have no debug name unfortunately",
+                        svg.as_bytes(),
+                        FitTo::Size(FIELD_SIZE as u32, FIELD_SIZE as u32),
+                    )
+                    .unwrap()
+                })
+                .collect(),
 
-            number_textures: [
-                NUMBER_ZERO,
-                NUMBER_ONE,
-                NUMBER_TWO,
-                NUMBER_THREE,
-                NUMBER_FOUR,
-                NUMBER_FIVE,
-                NUMBER_SIX,
-                NUMBER_SEVEN,
-                NUMBER_EIGHT,
-                NUMBER_NINE,
-                NUMBER_MINUS,
-            ]
-            .iter()
-            .map(|svg| {
-                RetainedImage::from_svg_bytes_with_size(
-                    "I have no debug name unfortunately",
-                    svg.as_bytes(),
-                    FitTo::Size(NUMBER_WIDTH as u32, NUMBER_HEIGHT as u32),
-                )
-                .unwrap()
-            })
-            .collect(),
+            number_textures: NUMBER_SVGS
+                .iter()
+                .map(|svg| {
+                    RetainedImage::from_svg_bytes_with_size(
+                        "I have no debug name unfortunately",
+                        svg.as_bytes(),
+                        FitTo::Size(NUMBER_WIDTH as u32, NUMBER_HEIGHT as u32),
+                    )
+                    .unwrap()
+                })
+                .collect(),
 
             number_bg_texture: RetainedImage::from_svg_bytes_with_size(
                 "I have a debug name: number background",
@@ -1,8 +0,0 @@
-use crate::number::Number;
-
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub(crate) enum Tile {
-    Empty,
-    Mine,
-    Number(Number),
-}
@@ -1,74 +1,110 @@
 use std::time::{Duration, Instant};
 
-use crate::{board::replay_board::get_time_modifier, get_in_replay_mode, get_replay_file};
-
 #[derive(Clone, Copy)]
-pub(crate) enum Time {
+enum State {
     None,
-    Playing(Instant),
+    // The clock read `offset` at `since`, and has been running at `rate` ever since.
+    Playing { since: Instant, offset: Duration },
     Paused(Duration),
     Ended(Duration),
 }
 
+// A clock in game time that can run slower or faster than real time. Changing the rate or seeking
+// starts counting again from the current reading, so neither makes it drift.
+#[derive(Clone, Copy)]
+pub(crate) struct Time {
+    state: State,
+    rate: f64,
+}
+
 impl Time {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: State::None,
+            rate: 1.0,
+        }
+    }
+
     pub(crate) fn reset(&mut self) {
-        *self = Self::None;
+        self.state = State::None;
     }
 
     pub(crate) fn start(&mut self) {
-        *self = Self::Playing(Instant::now());
+        self.state = State::Playing {
+            since: Instant::now(),
+            offset: Duration::ZERO,
+        };
     }
 
     pub(crate) fn pause(&mut self) {
-        if let Self::Playing(start_time) = self {
-            *self = Self::Paused(start_time.elapsed());
+        if let State::Playing { .. } = self.state {
+            self.state = State::Paused(self.duration());
         }
     }
 
     pub(crate) fn resume(&mut self) {
-        if let Self::Paused(duration) = self {
-            *self = Self::Playing(Instant::now() - *duration);
+        if let State::Paused(duration) = self.state {
+            self.state = State::Playing {
+                since: Instant::now(),
+                offset: duration,
+            };
         }
     }
 
-    pub(crate) fn end(&mut self) {
-        if let Self::Playing(start_time) = self {
-            *self = Self::Ended(start_time.elapsed());
-        } else if let Self::None = self {
-            *self = Self::Ended(Duration::from_secs(0));
+    pub(crate) fn toggle_pause(&mut self) {
+        match self.state {
+            State::None => self.start(),
+            State::Playing { .. } => self.pause(),
+            State::Paused(_) => self.resume(),
+            State::Ended(_) => {}
         }
     }
 
-    pub(crate) fn modifier_decreased(&mut self) {
-        match self {
-            Time::None => {}
-            Time::Playing(start_time) => *start_time -= start_time.elapsed(),
-            Time::Paused(duration) | Time::Ended(duration) => *duration *= 2,
+    pub(crate) fn end(&mut self) {
+        match self.state {
+            State::Playing { .. } | State::None => self.state = State::Ended(self.duration()),
+            State::Paused(_) | State::Ended(_) => {}
         }
     }
 
-    pub(crate) fn modifier_increased(&mut self) {
-        match self {
-            Time::None => {}
-            Time::Playing(start_time) => *start_time += start_time.elapsed() / 2,
-            Time::Paused(duration) | Time::Ended(duration) => *duration /= 2,
+    pub(crate) fn end_at(&mut self, duration: Duration) {
+        self.state = State::Ended(duration);
+    }
+
+    // Keeps playing if it was, everything else ends up paused at the given time.
+    pub(crate) fn seek(&mut self, duration: Duration) {
+        self.state = match self.state {
+            State::Playing { .. } => State::Playing {
+                since: Instant::now(),
+                offset: duration,
+            },
+            State::None | State::Paused(_) | State::Ended(_) => State::Paused(duration),
+        };
+    }
+
+    pub(crate) fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    pub(crate) fn set_rate(&mut self, rate: f64) {
+        if let State::Playing { .. } = self.state {
+            self.state = State::Playing {
+                since: Instant::now(),
+                offset: self.duration(),
+            };
         }
+        self.rate = rate;
     }
 
     pub(crate) fn duration(&self) -> Duration {
-        let res = get_time_modifier().apply(match self {
-            Self::None => Duration::from_secs(0),
-            Self::Playing(start_time) => start_time.elapsed(),
-            Self::Paused(duration) | Self::Ended(duration) => *duration,
-        });
-        if *get_in_replay_mode() {
-            res.min(get_replay_file().duration)
-        } else {
-            res
+        match self.state {
+            State::None => Duration::ZERO,
+            State::Playing { since, offset } => offset + since.elapsed().mul_f64(self.rate),
+            State::Paused(duration) | State::Ended(duration) => duration,
         }
     }
 
     pub(crate) fn is_playing(&self) -> bool {
-        matches!(self, Self::Playing(_))
+        matches!(self.state, State::Playing { .. })
     }
 }
@@ -1,13 +1,4 @@
-use std::{collections::HashSet, hash::Hash};
-
-pub(crate) fn has_unique_elements<T>(iter: T) -> bool
-where
-    T: IntoIterator,
-    T::Item: Eq + Hash,
-{
-    let mut uniq = HashSet::new();
-    iter.into_iter().all(move |x| uniq.insert(x))
-}
+use minesweeper_core::replay_file::{ReplayFile, ReplayFormat};
 
 pub(crate) fn ease_in_out_quad(x: f32) -> f32 {
     if x < 0.5 {
@@ -20,3 +11,19 @@ pub(crate) fn ease_in_out_quad(x: f32) -> f32 {
 pub(crate) fn lerp(a: f32, b: f32, t: f32) -> f32 {
     a + (b - a) * t
 }
+
+pub(crate) fn replay_file_name(replay_file: &ReplayFile, format: ReplayFormat) -> String {
+    chrono::Utc::now()
+        .format(
+            format!(
+                "minesweeper_{}x{}-{}_{:.3}_%d-%m-%Y_%H-%M-%S.{}",
+                replay_file.size.0,
+                replay_file.size.1,
+                replay_file.mines.len(),
+                replay_file.duration.as_secs_f32(),
+                format.extension()
+            )
+            .as_str(),
+        )
+        .to_string()
+}
@@ -1,6 +0,0 @@
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub(crate) enum Visibility {
-    Closed,
-    Open,
-    Flagged,
-}